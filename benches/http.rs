@@ -0,0 +1,72 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use spansy::http::{parse_request, parse_response};
+
+const SMALL_REQUEST: &[u8] = b"\
+    GET /home.html HTTP/1.1\r\n\
+    Host: developer.mozilla.org\r\n\
+    User-Agent: Mozilla/5.0 (Macintosh; Intel Mac OS X 10.9; rv:50.0) Gecko/20100101 Firefox/50.0\r\n\
+    Accept: text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8\r\n\
+    Connection: keep-alive\r\n\r\n";
+
+/// Builds a response with a large header block.
+fn large_header_response(count: usize) -> Vec<u8> {
+    let mut response = b"HTTP/1.1 200 OK\r\n".to_vec();
+    for i in 0..count {
+        response.extend_from_slice(format!("X-Header-{i}: value-{i}\r\n").as_bytes());
+    }
+    response.extend_from_slice(b"Content-Length: 4\r\n\r\npong");
+    response
+}
+
+fn bench_parse_request(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_request");
+
+    group.throughput(Throughput::Bytes(SMALL_REQUEST.len() as u64));
+    group.bench_function("small", |b| {
+        b.iter(|| parse_request(black_box(SMALL_REQUEST)).unwrap())
+    });
+
+    group.finish();
+}
+
+fn bench_parse_response(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_response");
+
+    let response = large_header_response(100);
+    group.throughput(Throughput::Bytes(response.len() as u64));
+    group.bench_function("100_headers", |b| {
+        b.iter(|| parse_response(black_box(&response)).unwrap())
+    });
+
+    group.finish();
+}
+
+fn bench_offset(c: &mut Criterion) {
+    let mut group = c.benchmark_group("offset");
+
+    let response = parse_response(&large_header_response(100)).unwrap();
+    group.bench_function("100_headers", |b| {
+        b.iter_batched_ref(
+            || response.clone(),
+            |response| response.offset(black_box(1024)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("100_headers_zero", |b| {
+        b.iter_batched_ref(
+            || response.clone(),
+            |response| response.offset(black_box(0)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_parse_request,
+    bench_parse_response,
+    bench_offset
+);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use spansy::json::parse_str;
+
+/// Builds a document of objects nested to the given depth.
+fn nested_object(depth: usize) -> String {
+    let mut src = String::new();
+    for _ in 0..depth {
+        src.push_str("{\"foo\": ");
+    }
+    src.push_str("42");
+    for _ in 0..depth {
+        src.push('}');
+    }
+    src
+}
+
+/// Builds an array with the given number of elements.
+fn wide_array(len: usize) -> String {
+    let elems = (0..len)
+        .map(|i| format!("{{\"id\": {i}, \"name\": \"item-{i}\", \"active\": true}}"))
+        .collect::<Vec<_>>();
+
+    format!("[{}]", elems.join(", "))
+}
+
+fn bench_parse_str(c: &mut Criterion) {
+    let mut group = c.benchmark_group("json_parse_str");
+
+    let nested = nested_object(64);
+    group.throughput(Throughput::Bytes(nested.len() as u64));
+    group.bench_function("nested_64", |b| {
+        b.iter(|| parse_str(black_box(&nested)).unwrap())
+    });
+
+    let wide = wide_array(1000);
+    group.throughput(Throughput::Bytes(wide.len() as u64));
+    group.bench_function("wide_1000", |b| {
+        b.iter(|| parse_str(black_box(&wide)).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_str);
+criterion_main!(benches);
@@ -19,6 +19,71 @@ pub(crate) fn get_span_range(src: &[u8], span: &[u8]) -> Range<usize> {
     span_start - src_start..span_end - src_start
 }
 
+/// Returns the range of the first occurrence of `needle` within the given range of the
+/// source, relative to the start of the source.
+///
+/// Unlike [`get_span_range`], this searches for the bytes rather than relying on a
+/// pointer into the source, so it can locate copies of the source bytes. Restricting the
+/// search range ensures a matching sequence elsewhere in the source is not found instead.
+pub(crate) fn find_range(src: &[u8], range: Range<usize>, needle: &[u8]) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+
+    src.get(range.clone())?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|idx| range.start + idx..range.start + idx + needle.len())
+}
+
+/// Returns the range of the bytes with leading and trailing optional whitespace removed.
+///
+/// Optional whitespace (OWS) is defined in RFC 9110 as any sequence of SP and HTAB.
+pub(crate) fn trim_ows(src: &[u8]) -> Range<usize> {
+    let is_ows = |b: &u8| *b == b' ' || *b == b'\t';
+
+    let start = src.iter().position(|b| !is_ows(b)).unwrap_or(src.len());
+    let end = src
+        .iter()
+        .rposition(|b| !is_ows(b))
+        .map_or(start, |idx| idx + 1);
+
+    start..end
+}
+
+/// Splits the bytes on the given delimiter, returning the range of each element.
+///
+/// Delimiters within a quoted string are ignored. Each element has surrounding optional
+/// whitespace removed, and empty elements are skipped as required by RFC 9110, section 5.6.1.
+pub(crate) fn split_list(src: &[u8], delim: u8) -> Vec<Range<usize>> {
+    let mut elems = Vec::new();
+    let mut push = |range: Range<usize>| {
+        let trimmed = trim_ows(&src[range.clone()]);
+        if !trimmed.is_empty() {
+            elems.push(range.start + trimmed.start..range.start + trimmed.end);
+        }
+    };
+
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (idx, b) in src.iter().enumerate() {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' if quoted => escaped = true,
+            b'"' => quoted = !quoted,
+            b if *b == delim && !quoted => {
+                push(start..idx);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    push(start..src.len());
+
+    elems
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,6 +98,36 @@ mod tests {
         assert_eq!(get_span_range(src, &src[3..6]), 3..6);
     }
 
+    #[test]
+    fn test_find_range() {
+        let src = b"GET /GET HTTP/1.1";
+
+        assert_eq!(find_range(src, 0..src.len(), b"GET"), Some(0..3));
+        assert_eq!(find_range(src, 1..src.len(), b"GET"), Some(5..8));
+        assert_eq!(find_range(src, 1..7, b"GET"), None);
+        assert_eq!(find_range(src, 0..src.len() + 1, b"GET"), None);
+        assert_eq!(find_range(src, 0..src.len(), b""), None);
+    }
+
+    #[test]
+    fn test_trim_ows() {
+        assert_eq!(trim_ows(b"foo"), 0..3);
+        assert_eq!(trim_ows(b" \tfoo bar \t"), 2..9);
+        assert_eq!(trim_ows(b"  "), 2..2);
+        assert_eq!(trim_ows(b""), 0..0);
+    }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(
+            split_list(b"gzip, deflate,br", b','),
+            vec![0..4, 6..13, 14..16]
+        );
+        assert_eq!(split_list(b" a ,, \t,b", b','), vec![1..2, 8..9]);
+        assert_eq!(split_list(b"a=\"x,\\\"y\", b", b','), vec![0..9, 11..12]);
+        assert!(split_list(b"  ", b',').is_empty());
+    }
+
     #[test]
     #[should_panic]
     fn test_get_span_range_outside_src_begin() {
@@ -0,0 +1,439 @@
+use bytes::Bytes;
+
+use crate::{
+    http::{parse_request_owned, parse_response_owned, Request, Response},
+    ParseError,
+};
+
+/// The start line of the message being built.
+#[derive(Debug, Clone)]
+enum StartLine {
+    Request { method: String, target: String },
+    Response { code: u16, reason: String },
+}
+
+/// A builder which synthesizes an HTTP/1.1 request or response.
+///
+/// The message is serialized into a buffer which is then parsed, so the spans of the
+/// built message are consistent with its source. Lines are terminated with CRLF and a
+/// `Content-Length` header is added for the body, unless a `Content-Length` or
+/// `Transfer-Encoding` header is provided.
+///
+/// The components are validated when the message is built, so they can not inject
+/// additional headers or messages, eg. with a header value containing CRLF.
+///
+/// # Example
+///
+/// ```
+/// use spansy::{http::Builder, Spanned};
+///
+/// let req = Builder::request("POST", "/api")
+///     .header("Host", "example.com")
+///     .body("{}")
+///     .build_request()
+///     .unwrap();
+///
+/// assert_eq!(
+///     req.span(),
+///     b"POST /api HTTP/1.1\r\nHost: example.com\r\nContent-Length: 2\r\n\r\n{}".as_slice()
+/// );
+/// assert_eq!(req.body.unwrap().span().indices(), 60..62);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Builder {
+    start: StartLine,
+    headers: Vec<(String, Vec<u8>)>,
+    body: Option<Vec<u8>>,
+}
+
+impl Builder {
+    /// Returns a builder for a request with the given method and target.
+    pub fn request(method: impl Into<String>, target: impl Into<String>) -> Self {
+        Self::new(StartLine::Request {
+            method: method.into(),
+            target: target.into(),
+        })
+    }
+
+    /// Returns a builder for a response with the given status code and reason phrase.
+    pub fn response(code: u16, reason: impl Into<String>) -> Self {
+        Self::new(StartLine::Response {
+            code,
+            reason: reason.into(),
+        })
+    }
+
+    fn new(start: StartLine) -> Self {
+        Self {
+            start,
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Appends a header.
+    pub fn header(mut self, name: impl Into<String>, value: impl AsRef<[u8]>) -> Self {
+        self.headers.push((name.into(), value.as_ref().to_vec()));
+        self
+    }
+
+    /// Sets the body.
+    pub fn body(mut self, body: impl AsRef<[u8]>) -> Self {
+        self.body = Some(body.as_ref().to_vec());
+        self
+    }
+
+    /// Returns the serialized message.
+    pub fn to_bytes(&self) -> Bytes {
+        let mut buf = Vec::new();
+        match &self.start {
+            StartLine::Request { method, target } => {
+                buf.extend_from_slice(format!("{method} {target} HTTP/1.1\r\n").as_bytes())
+            }
+            StartLine::Response { code, reason } => {
+                buf.extend_from_slice(format!("HTTP/1.1 {code} {reason}\r\n").as_bytes())
+            }
+        }
+
+        for (name, value) in &self.headers {
+            buf.extend_from_slice(name.as_bytes());
+            buf.extend_from_slice(b": ");
+            buf.extend_from_slice(value);
+            buf.extend_from_slice(b"\r\n");
+        }
+
+        let framed = self.headers.iter().any(|(name, _)| {
+            name.eq_ignore_ascii_case("content-length")
+                || name.eq_ignore_ascii_case("transfer-encoding")
+        });
+        if !framed {
+            let len = match (&self.start, &self.body) {
+                (_, Some(body)) => Some(body.len()),
+                // A response must be framed, unless its status code excludes a body.
+                (StartLine::Response { code, .. }, None) => {
+                    (!matches!(code, 100..=199 | 204 | 304)).then_some(0)
+                }
+                (StartLine::Request { .. }, None) => None,
+            };
+            if let Some(len) = len {
+                buf.extend_from_slice(format!("Content-Length: {len}\r\n").as_bytes());
+            }
+        }
+
+        buf.extend_from_slice(b"\r\n");
+        if let Some(body) = &self.body {
+            buf.extend_from_slice(body);
+        }
+
+        buf.into()
+    }
+
+    /// Builds the request.
+    ///
+    /// Returns an error if the builder is for a response, if any component is invalid,
+    /// eg. a header name which is not a token or a value containing a line terminator,
+    /// or if the serialized request is not parsed in its entirety.
+    pub fn build_request(&self) -> Result<Request, ParseError> {
+        if !matches!(self.start, StartLine::Request { .. }) {
+            return Err(ParseError("builder is not for a request".to_string()));
+        }
+        self.validate()?;
+
+        let src = self.to_bytes();
+        let request = parse_request_owned(src.clone())?;
+        check_complete(request.span.len(), src.len())?;
+
+        Ok(request)
+    }
+
+    /// Builds the response.
+    ///
+    /// Returns an error if the builder is for a request, if any component is invalid,
+    /// eg. a reason phrase containing a line terminator, if the status code excludes a
+    /// body but a body is set, or if the serialized response is not parsed in its
+    /// entirety.
+    pub fn build_response(&self) -> Result<Response, ParseError> {
+        let StartLine::Response { code, .. } = &self.start else {
+            return Err(ParseError("builder is not for a response".to_string()));
+        };
+        self.validate()?;
+
+        if matches!(code, 100..=199 | 204 | 304) && self.body.is_some() {
+            return Err(ParseError(format!(
+                "response with status code {code} can not have a body"
+            )));
+        }
+
+        let src = self.to_bytes();
+        let response = parse_response_owned(src.clone())?;
+        check_complete(response.span.len(), src.len())?;
+
+        Ok(response)
+    }
+
+    /// Checks that the components can be serialized without changing the structure of
+    /// the message.
+    fn validate(&self) -> Result<(), ParseError> {
+        match &self.start {
+            StartLine::Request { method, target } => {
+                if !is_token(method.as_bytes()) {
+                    return Err(ParseError(format!("invalid method: {method:?}")));
+                }
+                if !is_request_target(target.as_bytes()) {
+                    return Err(ParseError(format!("invalid request target: {target:?}")));
+                }
+            }
+            StartLine::Response { code, reason } => {
+                if !(100..=999).contains(code) {
+                    return Err(ParseError(format!("invalid status code: {code}")));
+                }
+                if has_line_terminator(reason.as_bytes()) {
+                    return Err(ParseError(format!("invalid reason phrase: {reason:?}")));
+                }
+            }
+        }
+
+        for (name, value) in &self.headers {
+            if !is_token(name.as_bytes()) {
+                return Err(ParseError(format!("invalid header name: {name:?}")));
+            }
+            if has_line_terminator(value) {
+                return Err(ParseError(format!(
+                    "header \"{name}\" value contains a line terminator"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` if the bytes are a token, see RFC 9110, section 5.6.2.
+fn is_token(src: &[u8]) -> bool {
+    !src.is_empty()
+        && src.iter().all(|b| {
+            b.is_ascii_alphanumeric()
+                || matches!(
+                    b,
+                    b'!' | b'#'
+                        | b'$'
+                        | b'%'
+                        | b'&'
+                        | b'\''
+                        | b'*'
+                        | b'+'
+                        | b'-'
+                        | b'.'
+                        | b'^'
+                        | b'_'
+                        | b'`'
+                        | b'|'
+                        | b'~'
+                )
+        })
+}
+
+/// Returns `true` if the bytes only contain characters allowed in a request target, see
+/// RFC 9112, section 3.2.
+///
+/// These are the characters of a URI path, query and authority, so the form of the
+/// target is not validated.
+fn is_request_target(src: &[u8]) -> bool {
+    !src.is_empty()
+        && src.iter().all(|b| {
+            b.is_ascii_alphanumeric()
+                || matches!(
+                    b,
+                    // unreserved
+                    b'-' | b'.' | b'_' | b'~'
+                        // pct-encoded
+                        | b'%'
+                        // sub-delims
+                        | b'!' | b'$' | b'&' | b'\'' | b'(' | b')'
+                        | b'*' | b'+' | b',' | b';' | b'='
+                        // pchar, path and query delimiters
+                        | b':' | b'@' | b'/' | b'?'
+                        // IP literals of an authority
+                        | b'[' | b']'
+                )
+        })
+}
+
+/// Returns `true` if the bytes contain a CR or LF.
+fn has_line_terminator(src: &[u8]) -> bool {
+    src.iter().any(|b| matches!(b, b'\r' | b'\n'))
+}
+
+/// Checks that the parsed message covers the entire serialized message.
+fn check_complete(parsed: usize, len: usize) -> Result<(), ParseError> {
+    if parsed != len {
+        return Err(ParseError(format!(
+            "parsed message length {parsed} does not match serialized length {len}"
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Spanned;
+
+    use super::*;
+
+    #[test]
+    fn test_build_request() {
+        let builder = Builder::request("POST", "/a?b=c")
+            .header("Host", "example.com")
+            .header("X-Bin", b"\xe9\xff")
+            .body(b"hello");
+
+        let src = builder.to_bytes();
+        assert_eq!(
+            src,
+            b"POST /a?b=c HTTP/1.1\r\nHost: example.com\r\nX-Bin: \xe9\xff\r\nContent-Length: 5\r\n\r\nhello"
+                .as_slice()
+        );
+
+        let req = builder.build_request().unwrap();
+        assert_eq!(req.span(), src.as_ref());
+        assert_eq!(req.request.method.as_str(), "POST");
+        assert_eq!(req.request.target.as_str(), "/a?b=c");
+        assert_eq!(req.headers.len(), 3);
+        assert_eq!(req.headers[1].value.as_bytes(), b"\xe9\xff");
+        assert_eq!(req.body.as_ref().unwrap().as_bytes(), b"hello");
+
+        // Every span indexes into the serialized message.
+        for header in &req.headers {
+            let start = header.span().indices().min().unwrap();
+            assert_eq!(
+                &src[start..][..header.span().len()],
+                header.span().as_bytes()
+            );
+        }
+
+        let req = Builder::request("GET", "/").build_request().unwrap();
+        assert_eq!(req.span(), b"GET / HTTP/1.1\r\n\r\n".as_slice());
+
+        assert!(Builder::request("GET", "/")
+            .header("Bad Name", "x")
+            .build_request()
+            .is_err());
+        assert!(Builder::response(200, "OK").build_request().is_err());
+    }
+
+    #[test]
+    fn test_build_response() {
+        let res = Builder::response(200, "OK")
+            .header("Content-Type", "text/plain")
+            .body("hi")
+            .build_response()
+            .unwrap();
+        assert_eq!(
+            res.span(),
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\r\nhi"
+                .as_slice()
+        );
+        assert_eq!(res.body.unwrap().span().indices(), &(64..66));
+
+        let res = Builder::response(404, "Not Found")
+            .build_response()
+            .unwrap();
+        assert_eq!(
+            res.span(),
+            b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".as_slice()
+        );
+
+        let res = Builder::response(204, "No Content")
+            .build_response()
+            .unwrap();
+        assert!(res.headers.is_empty());
+
+        let res = Builder::response(200, "OK")
+            .header("Content-Length", "3")
+            .body("abc")
+            .build_response()
+            .unwrap();
+        assert_eq!(res.headers.len(), 1);
+
+        assert!(Builder::response(304, "Not Modified")
+            .body("x")
+            .build_response()
+            .is_err());
+        assert!(Builder::request("GET", "/").build_response().is_err());
+    }
+
+    #[test]
+    fn test_build_rejects_injection() {
+        assert!(Builder::request("GET", "/")
+            .header("X", "a\r\nEvil: 1")
+            .build_request()
+            .is_err());
+        assert!(Builder::request("GET", "/")
+            .header("X", "a\r\n\r\ntrailing")
+            .build_request()
+            .is_err());
+        assert!(Builder::request("GET", "/")
+            .header("X", "a\nb")
+            .build_request()
+            .is_err());
+        assert!(Builder::request("GET", "/")
+            .header("X:Y", "a")
+            .build_request()
+            .is_err());
+        assert!(Builder::request("GET", "/")
+            .header("", "a")
+            .build_request()
+            .is_err());
+        assert!(Builder::request("GET /a HTTP/1.1\r\n", "/")
+            .build_request()
+            .is_err());
+        assert!(Builder::request("GET", "/ HTTP/1.1\r\nEvil: 1\r\nX:")
+            .build_request()
+            .is_err());
+        assert!(Builder::request("GET", "").build_request().is_err());
+        for target in [
+            "/a\0", "/a\tb", "/a\x7f", "/\u{e9}", "/a#b", "/a\"b", "/a<b>",
+        ] {
+            assert!(
+                Builder::request("GET", target).build_request().is_err(),
+                "{target:?}"
+            );
+        }
+        for target in [
+            "*",
+            "example.com:443",
+            "http://[::1]:80/a?b=c&d=%20",
+            "/a;b,c@d!$'()+=",
+        ] {
+            assert!(
+                Builder::request("GET", target).build_request().is_ok(),
+                "{target:?}"
+            );
+        }
+
+        assert!(Builder::response(200, "OK\r\nSet-Cookie: x")
+            .build_response()
+            .is_err());
+        assert!(Builder::response(200, "OK")
+            .header("X", "a\r\nSet-Cookie: x")
+            .build_response()
+            .is_err());
+        assert!(Builder::response(42, "OK").build_response().is_err());
+    }
+
+    #[test]
+    fn test_build_rejects_incomplete() {
+        // The body extends beyond the provided Content-Length.
+        assert!(Builder::request("POST", "/")
+            .header("Content-Length", "1")
+            .body("abc")
+            .build_request()
+            .is_err());
+        assert!(Builder::response(200, "OK")
+            .header("Content-Length", "1")
+            .body("abc")
+            .build_response()
+            .is_err());
+    }
+}
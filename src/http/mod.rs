@@ -1,91 +1,468 @@
 //! HTTP span parsing.
 
+mod builder;
 mod span;
 mod types;
+mod visit;
+
+use std::{collections::VecDeque, iter::FusedIterator};
 
 use bytes::Bytes;
 
-pub use span::{parse_request, parse_response};
+pub use builder::Builder;
+pub use span::{
+    parse_request, parse_request_head, parse_request_line, parse_request_owned, parse_response,
+    parse_response_folded, parse_response_for_method, parse_response_lenient, parse_response_owned,
+    parse_status_line,
+};
 pub use types::{
-    Body, Code, Header, HeaderName, HeaderValue, Method, Reason, Request, RequestLine, Response,
-    Status, Target,
+    authority_host, authority_port, base64_decode, Base64Alphabet, Body, BodyFraming,
+    BodyLengthConflict, Code, ContentRange, FieldRef, Header, HeaderName, HeaderValue, HttpDate,
+    Method, MethodKind, Reason, Request, RequestLine, Response, Status, Target,
 };
+pub use visit::HttpVisit;
 
 use crate::ParseError;
 
-use self::span::{parse_request_from_bytes, parse_response_from_bytes};
+use self::span::{parse_request_from_bytes, parse_response_from_bytes, ParseOptions};
 /// An iterator yielding parsed HTTP requests.
+///
+/// The iterator stops after yielding an error, as the start of the next request can not
+/// be determined. See [`Requests::new_resync`] to continue after malformed requests.
 #[derive(Debug)]
 pub struct Requests {
     src: Bytes,
     /// The current position in the source string.
     pos: usize,
+    /// Whether to continue at the next message boundary after an error.
+    resync: bool,
+    /// Whether the iterator stopped after an error.
+    failed: bool,
 }
 
 impl Requests {
     /// Returns a new `Requests` iterator.
     pub fn new(src: Bytes) -> Self {
-        Self { src, pos: 0 }
+        Self {
+            src,
+            pos: 0,
+            resync: false,
+            failed: false,
+        }
     }
 
     /// Returns a new `Requests` iterator.
     pub fn new_from_slice(src: &[u8]) -> Self {
+        Self::new(Bytes::copy_from_slice(src))
+    }
+
+    /// Returns a new `Requests` iterator which continues after malformed requests.
+    ///
+    /// After yielding an error, parsing continues following the next empty line, ie. the
+    /// end of the malformed request head. If the malformed request has a body, the body
+    /// is likely to be yielded as another error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::Requests;
+    ///
+    /// let src = b"GET /a HTTP/1.1\r\n\r\nGARBAGE\r\n\r\nGET /b HTTP/1.1\r\n\r\n";
+    ///
+    /// let reqs = Requests::new_resync(src.to_vec().into()).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(reqs.len(), 3);
+    /// assert!(reqs[1].is_err());
+    /// assert_eq!(reqs[2].as_ref().unwrap().request.target.as_str(), "/b");
+    /// ```
+    pub fn new_resync(src: Bytes) -> Self {
         Self {
-            src: Bytes::copy_from_slice(src),
-            pos: 0,
+            resync: true,
+            ..Self::new(src)
         }
     }
+
+    /// Returns the position in the source of the next request to be parsed.
+    ///
+    /// This is the total length of the requests parsed so far.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::Requests;
+    ///
+    /// let mut reqs = Requests::new_from_slice(b"GET / HTTP/1.1\r\n\r\nGET /a HTTP/1.1\r\n");
+    ///
+    /// assert!(reqs.next().unwrap().is_ok());
+    /// assert_eq!(reqs.position(), 18);
+    ///
+    /// // The second request is incomplete.
+    /// assert!(reqs.next().unwrap().is_err());
+    /// assert_eq!(reqs.remaining(), b"GET /a HTTP/1.1\r\n");
+    /// ```
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the source which has not been parsed yet.
+    pub fn remaining(&self) -> &[u8] {
+        &self.src[self.pos.min(self.src.len())..]
+    }
 }
 
 impl Iterator for Requests {
     type Item = Result<Request, ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.pos >= self.src.len() {
-            None
-        } else {
-            Some(parse_request_from_bytes(&self.src, self.pos).map(|req| {
-                self.pos += req.span.len();
-                req
-            }))
+        if self.failed || self.pos >= self.src.len() {
+            return None;
         }
+
+        let req = parse_request_from_bytes(&self.src, self.pos);
+        match &req {
+            Ok(req) => self.pos += req.span.len(),
+            Err(_) if self.resync => self.pos = next_boundary(&self.src, self.pos),
+            Err(_) => self.failed = true,
+        }
+
+        Some(req)
     }
 }
 
+impl FusedIterator for Requests {}
+
+/// Returns the position following the next empty line after `pos`, or the end of the
+/// source if there is none.
+fn next_boundary(src: &[u8], pos: usize) -> usize {
+    src[pos..]
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map_or(src.len(), |idx| pos + idx + 4)
+}
+
 /// An iterator yielding parsed HTTP responses.
+///
+/// The iterator stops after yielding an error, as the start of the next response can
+/// not be determined. See [`Responses::new_resync`] to continue after malformed
+/// responses.
 #[derive(Debug)]
 pub struct Responses {
     src: Bytes,
     /// The current position in the source string.
     pos: usize,
+    /// The methods of the requests corresponding to the remaining responses.
+    methods: VecDeque<Method>,
+    /// Whether to continue at the next message boundary after an error.
+    resync: bool,
+    /// Whether the iterator stopped after an error.
+    failed: bool,
 }
 
 impl Responses {
     /// Returns a new `Responses` iterator.
+    ///
+    /// All responses are assumed to be responses to requests other than HEAD, see
+    /// [`Responses::new_for_methods`].
     pub fn new(src: Bytes) -> Self {
-        Self { src, pos: 0 }
+        Self {
+            src,
+            pos: 0,
+            methods: VecDeque::new(),
+            resync: false,
+            failed: false,
+        }
     }
 
     /// Returns a new `Responses` iterator.
     pub fn new_from_slice(src: &[u8]) -> Self {
+        Self::new(Bytes::copy_from_slice(src))
+    }
+
+    /// Returns a new `Responses` iterator which continues after malformed responses.
+    ///
+    /// After yielding an error, parsing continues following the next empty line, ie. the
+    /// end of the malformed response head, see [`Requests::new_resync`].
+    pub fn new_resync(src: Bytes) -> Self {
         Self {
-            src: Bytes::copy_from_slice(src),
-            pos: 0,
+            resync: true,
+            ..Self::new(src)
         }
     }
+
+    /// Returns a new `Responses` iterator for responses to requests with the given methods,
+    /// in order.
+    ///
+    /// Whether a response has a body depends on the method of the request it responds to:
+    /// a response to a HEAD request never has a body, even if it has a `Content-Length`
+    /// header. Responses beyond the provided methods are assumed to be responses to
+    /// requests other than HEAD.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::{Requests, Responses};
+    ///
+    /// let reqs = b"HEAD / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n";
+    /// let resps = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n\
+    ///     HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nbody";
+    ///
+    /// let methods = Requests::new_from_slice(reqs)
+    ///     .map(|req| req.map(|req| req.request.method))
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    ///
+    /// let resps = Responses::new_for_methods(resps.to_vec().into(), methods)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    ///
+    /// assert!(resps[0].body.is_none());
+    /// assert_eq!(resps[1].body.as_ref().unwrap().as_bytes(), b"body");
+    /// ```
+    pub fn new_for_methods(src: Bytes, methods: Vec<Method>) -> Self {
+        Self {
+            methods: methods.into(),
+            ..Self::new(src)
+        }
+    }
+
+    /// Returns an iterator which only yields final responses, skipping interim responses
+    /// with a 1xx (Informational) status code.
+    ///
+    /// The interim responses preceding each final response are available from
+    /// [`SkipInformational::interim`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::Responses;
+    ///
+    /// let src = b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+    ///
+    /// let mut resps = Responses::new_from_slice(src).skip_informational();
+    ///
+    /// let resp = resps.next().unwrap().unwrap();
+    /// assert_eq!(resp.status.code.as_str(), "200");
+    /// assert_eq!(resps.interim()[0].status.code.as_str(), "100");
+    /// assert!(resps.next().is_none());
+    /// ```
+    pub fn skip_informational(self) -> SkipInformational {
+        SkipInformational {
+            inner: self,
+            interim: Vec::new(),
+        }
+    }
+
+    /// Returns the position in the source of the next response to be parsed.
+    ///
+    /// This is the total length of the responses parsed so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the source which has not been parsed yet.
+    pub fn remaining(&self) -> &[u8] {
+        &self.src[self.pos.min(self.src.len())..]
+    }
 }
 
 impl Iterator for Responses {
     type Item = Result<Response, ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.pos >= self.src.len() {
-            None
-        } else {
-            Some(parse_response_from_bytes(&self.src, self.pos).map(|resp| {
+        if self.failed || self.pos >= self.src.len() {
+            return None;
+        }
+
+        let head = self
+            .methods
+            .front()
+            .is_some_and(|method| method.as_str() == "HEAD");
+
+        let resp = parse_response_from_bytes(
+            &self.src,
+            self.pos,
+            ParseOptions {
+                head,
+                ..Default::default()
+            },
+        );
+        match &resp {
+            Ok(resp) => {
+                self.pos += resp.span.len();
+                // An interim response is followed by another response to the same request.
+                if !resp.is_informational() {
+                    self.methods.pop_front();
+                }
+            }
+            Err(_) if self.resync => {
+                self.pos = next_boundary(&self.src, self.pos);
+                self.methods.pop_front();
+            }
+            Err(_) => self.failed = true,
+        }
+
+        Some(resp)
+    }
+}
+
+impl FusedIterator for Responses {}
+
+/// An iterator yielding the final responses of a [`Responses`] iterator, see
+/// [`Responses::skip_informational`].
+#[derive(Debug)]
+pub struct SkipInformational {
+    inner: Responses,
+    /// The interim responses preceding the last yielded response.
+    interim: Vec<Response>,
+}
+
+impl SkipInformational {
+    /// Returns the interim responses which preceded the last yielded response, in order.
+    ///
+    /// After the iterator is exhausted, these are any trailing interim responses which
+    /// are not followed by a final response.
+    pub fn interim(&self) -> &[Response] {
+        &self.interim
+    }
+
+    /// Returns the underlying `Responses` iterator.
+    pub fn into_inner(self) -> Responses {
+        self.inner
+    }
+}
+
+impl Iterator for SkipInformational {
+    type Item = Result<Response, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut interim = Vec::new();
+        let resp = loop {
+            match self.inner.next() {
+                Some(Ok(resp)) if resp.is_informational() => interim.push(resp),
+                resp => break resp,
+            }
+        };
+        self.interim = interim;
+
+        resp
+    }
+}
+
+impl FusedIterator for SkipInformational {}
+
+/// An HTTP message, either a request or a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Message {
+    /// An HTTP request.
+    Request(Request),
+    /// An HTTP response.
+    Response(Response),
+}
+
+/// An iterator yielding parsed HTTP requests and responses from a transcript of a
+/// connection.
+///
+/// Each message is parsed as a response if it starts with `HTTP/`, the start of the
+/// version in a status line, and as a request otherwise. A request line can not start
+/// with `HTTP/` as it starts with the method, which is a token and can not contain `/`.
+///
+/// Responses are parsed in the context of the preceding requests, so a response to a
+/// HEAD request has no body, see [`Responses::new_for_methods`].
+///
+/// # Example
+///
+/// ```
+/// use spansy::http::{Message, Transcript};
+///
+/// let src = b"HEAD / HTTP/1.1\r\n\r\n\
+///     HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n\
+///     GET / HTTP/1.1\r\n\r\n\
+///     HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nbody";
+///
+/// let msgs = Transcript::new_from_slice(src)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(msgs.len(), 4);
+/// assert!(matches!(&msgs[1], Message::Response(resp) if resp.body.is_none()));
+/// assert!(matches!(&msgs[3], Message::Response(resp) if resp.body.is_some()));
+/// ```
+#[derive(Debug)]
+pub struct Transcript {
+    src: Bytes,
+    /// The current position in the source string.
+    pos: usize,
+    /// The methods of the requests which have not been responded to.
+    methods: VecDeque<Method>,
+    /// Whether the iterator stopped after an error.
+    failed: bool,
+}
+
+impl Transcript {
+    /// Returns a new `Transcript` iterator.
+    pub fn new(src: Bytes) -> Self {
+        Self {
+            src,
+            pos: 0,
+            methods: VecDeque::new(),
+            failed: false,
+        }
+    }
+
+    /// Returns a new `Transcript` iterator.
+    pub fn new_from_slice(src: &[u8]) -> Self {
+        Self::new(Bytes::copy_from_slice(src))
+    }
+}
+
+impl Iterator for Transcript {
+    type Item = Result<Message, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.pos >= self.src.len() {
+            return None;
+        }
+
+        let msg = self.next_message();
+        self.failed = msg.is_err();
+
+        Some(msg)
+    }
+}
+
+impl FusedIterator for Transcript {}
+
+impl Transcript {
+    /// Parses the message at the current position.
+    fn next_message(&mut self) -> Result<Message, ParseError> {
+        if self.src[self.pos..].starts_with(b"HTTP/") {
+            let head = self
+                .methods
+                .front()
+                .is_some_and(|method| method.as_str() == "HEAD");
+
+            parse_response_from_bytes(
+                &self.src,
+                self.pos,
+                ParseOptions {
+                    head,
+                    ..Default::default()
+                },
+            )
+            .map(|resp| {
                 self.pos += resp.span.len();
-                resp
-            }))
+                if !resp.is_informational() {
+                    self.methods.pop_front();
+                }
+                Message::Response(resp)
+            })
+        } else {
+            parse_request_from_bytes(&self.src, self.pos).map(|req| {
+                self.pos += req.span.len();
+                self.methods.push_back(req.request.method.clone());
+                Message::Request(req)
+            })
         }
     }
 }
@@ -197,6 +574,220 @@ mod tests {
         assert!(resps[2].body.is_none());
     }
 
+    #[test]
+    fn test_parse_position() {
+        let mut reqs = Requests::new_from_slice(MULTIPLE_REQUESTS);
+        assert_eq!(reqs.position(), 0);
+        assert_eq!(reqs.remaining(), MULTIPLE_REQUESTS);
+
+        let req = reqs.next().unwrap().unwrap();
+        assert_eq!(reqs.position(), req.span().len());
+        assert_eq!(reqs.remaining(), &MULTIPLE_REQUESTS[req.span().len()..]);
+
+        assert!(reqs.next().unwrap().is_ok());
+        assert_eq!(reqs.position(), MULTIPLE_REQUESTS.len());
+        assert!(reqs.remaining().is_empty());
+
+        let mut resps = Responses::new_from_slice(MULTIPLE_RESPONSES);
+        resps.next().unwrap().unwrap();
+        assert_eq!(resps.position(), 38);
+        assert!(resps
+            .remaining()
+            .starts_with(b"HTTP/1.1 200 OK\r\nContent-Length: 14"));
+    }
+
+    #[test]
+    fn test_parse_stops_after_error() {
+        let src = b"GET /a HTTP/1.1\r\n\r\nGARBAGE\r\n\r\nGET /b HTTP/1.1\r\n\r\n";
+
+        let mut reqs = Requests::new_from_slice(src);
+        assert!(reqs.next().unwrap().is_ok());
+        assert!(reqs.next().unwrap().is_err());
+        assert!(reqs.next().is_none());
+        assert_eq!(reqs.remaining(), &src[19..]);
+
+        let mut resps = Responses::new_from_slice(b"HTTP/1.1 200 OK\r\n\r\n");
+        assert!(resps.next().unwrap().is_err());
+        assert!(resps.next().is_none());
+
+        let mut msgs = Transcript::new_from_slice(src);
+        assert!(msgs.next().unwrap().is_ok());
+        assert!(msgs.next().unwrap().is_err());
+        assert!(msgs.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_fused() {
+        let mut reqs = Requests::new_from_slice(b"GARBAGE\r\n\r\n");
+        assert!(reqs.next().unwrap().is_err());
+        for _ in 0..3 {
+            assert!(reqs.next().is_none());
+        }
+
+        let mut resps = Responses::new_from_slice(b"GARBAGE\r\n\r\n");
+        assert!(resps.next().unwrap().is_err());
+        for _ in 0..3 {
+            assert!(resps.next().is_none());
+        }
+
+        let mut msgs = Transcript::new_from_slice(b"GARBAGE\r\n\r\n");
+        assert!(msgs.next().unwrap().is_err());
+        for _ in 0..3 {
+            assert!(msgs.next().is_none());
+        }
+
+        let mut reqs = Requests::new_from_slice(b"GET / HTTP/1.1\r\n\r\n");
+        assert!(reqs.next().unwrap().is_ok());
+        for _ in 0..3 {
+            assert!(reqs.next().is_none());
+        }
+    }
+
+    #[test]
+    fn test_parse_resync() {
+        let src = b"GET /a HTTP/1.1\r\n\r\nGARBAGE\r\n\r\nGET /b HTTP/1.1\r\n\r\n";
+
+        let reqs = Requests::new_resync(Bytes::from_static(src)).collect::<Vec<_>>();
+        assert_eq!(reqs.len(), 3);
+        assert_eq!(reqs[0].as_ref().unwrap().request.target.as_str(), "/a");
+        assert!(reqs[1].is_err());
+        assert_eq!(reqs[2].as_ref().unwrap().request.target.as_str(), "/b");
+        assert_eq!(reqs[2].as_ref().unwrap().span().indices(), &(30..src.len()));
+
+        // The malformed response has no terminating empty line.
+        let src = b"HTTP/1.1 204 No Content\r\n\r\nHTTP/1.1 abc\r\n\r\nHTTP/1.1 200 OK\r\n";
+        let resps = Responses::new_resync(Bytes::from_static(src)).collect::<Vec<_>>();
+        assert_eq!(resps.len(), 3);
+        assert!(resps[0].is_ok());
+        assert!(resps[1].is_err());
+        assert!(resps[2].is_err());
+    }
+
+    #[test]
+    fn test_parse_responses_skip_informational() {
+        let src = b"HTTP/1.1 100 Continue\r\n\r\n\
+            HTTP/1.1 103 Early Hints\r\nLink: </a.css>\r\n\r\n\
+            HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nhi\
+            HTTP/1.1 204 No Content\r\n\r\n\
+            HTTP/1.1 100 Continue\r\n\r\n";
+
+        let resps = Responses::new_from_slice(src)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(resps.len(), 5);
+
+        let mut resps = Responses::new_from_slice(src).skip_informational();
+
+        let resp = resps.next().unwrap().unwrap();
+        assert_eq!(resp.status.code.as_str(), "200");
+        assert_eq!(resp.body.unwrap().as_bytes(), b"hi");
+        assert_eq!(
+            resps
+                .interim()
+                .iter()
+                .map(|resp| resp.status.code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["100", "103"]
+        );
+
+        let resp = resps.next().unwrap().unwrap();
+        assert_eq!(resp.status.code.as_str(), "204");
+        assert!(resps.interim().is_empty());
+
+        assert!(resps.next().is_none());
+        assert_eq!(resps.interim().len(), 1);
+        assert!(resps.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_responses_informational_for_methods() {
+        // The interim response does not consume the method of the HEAD request.
+        let methods = Requests::new_from_slice(b"HEAD / HTTP/1.1\r\n\r\n")
+            .map(|req| req.map(|req| req.request.method))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let src = b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n";
+
+        let resps = Responses::new_for_methods(Bytes::from_static(src), methods)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(resps.len(), 2);
+        assert!(resps[1].body.is_none());
+
+        let src = b"HEAD / HTTP/1.1\r\n\r\n\
+            HTTP/1.1 100 Continue\r\n\r\n\
+            HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n";
+        let msgs = Transcript::new_from_slice(src)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(msgs.len(), 3);
+        assert!(matches!(&msgs[2], Message::Response(resp) if resp.body.is_none()));
+    }
+
+    #[test]
+    fn test_parse_responses_for_methods() {
+        let methods = Requests::new_from_slice(
+            b"GET / HTTP/1.1\r\n\r\nHEAD / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n",
+        )
+        .map(|req| req.map(|req| req.request.method))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+        let resps = Responses::new_for_methods(
+            Bytes::from_static(
+                b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nhi\
+                HTTP/1.1 200 OK\r\nContent-Length: 14\r\n\r\n\
+                HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello",
+            ),
+            methods,
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+        assert_eq!(resps.len(), 3);
+        assert_eq!(resps[0].body.as_ref().unwrap().span(), b"hi".as_slice());
+        assert!(resps[1].body.is_none());
+        assert_eq!(resps[2].body.as_ref().unwrap().span(), b"hello".as_slice());
+
+        // Without the methods the HEAD response consumes the next response as its body.
+        assert!(Responses::new_from_slice(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 14\r\n\r\nHTTP/1.1 200 OK\r\n"
+        )
+        .next()
+        .unwrap()
+        .unwrap()
+        .body
+        .is_some());
+    }
+
+    #[test]
+    fn test_parse_transcript() {
+        let src = [MULTIPLE_REQUESTS, MULTIPLE_RESPONSES].concat();
+
+        let msgs = Transcript::new_from_slice(&src)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let reqs = Requests::new_from_slice(MULTIPLE_REQUESTS)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut resps = Responses::new_from_slice(MULTIPLE_RESPONSES)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        resps
+            .iter_mut()
+            .for_each(|resp| resp.offset(MULTIPLE_REQUESTS.len()));
+
+        assert_eq!(msgs.len(), 5);
+        assert_eq!(msgs[0], Message::Request(reqs[0].clone()));
+        assert_eq!(msgs[1], Message::Request(reqs[1].clone()));
+        assert_eq!(msgs[2], Message::Response(resps[0].clone()));
+        assert_eq!(msgs[4], Message::Response(resps[2].clone()));
+
+        let mut transcript = Transcript::new_from_slice(b"HTTP/1.1 200 OK\r\n\r\n");
+        assert!(transcript.next().unwrap().is_err());
+    }
+
     #[test]
     fn test_parse_request_duplicate_headers() {
         let req_bytes = b"GET / HTTP/1.1\r\nHost: localhost\r\nAccept: application/json\r\n\
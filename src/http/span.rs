@@ -1,23 +1,154 @@
+use std::ops::Range;
+
 use bytes::Bytes;
 
 use crate::{
-    helpers::get_span_range,
+    helpers::{find_range, get_span_range, trim_ows},
     http::{
         Body, Code, Header, HeaderName, HeaderValue, Method, Reason, Request, RequestLine,
         Response, Status, Target,
     },
-    ParseError, Span,
+    ParseError, Source, Span,
 };
 
 const MAX_HEADERS: usize = 128;
 
+/// Options controlling how an HTTP message is parsed.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ParseOptions {
+    /// Whether the message is a response to a HEAD request, which never has a body.
+    pub(crate) head: bool,
+    /// Whether bare LF line terminators are accepted in addition to CRLF.
+    pub(crate) lenient: bool,
+    /// Whether header values folded across multiple lines are joined, which is only
+    /// supported for responses.
+    pub(crate) fold: bool,
+}
+
 /// Parses an HTTP request.
 pub fn parse_request(src: &[u8]) -> Result<Request, ParseError> {
-    parse_request_from_bytes(&Bytes::copy_from_slice(src), 0)
+    parse_request_owned(Bytes::copy_from_slice(src))
+}
+
+/// Parses an HTTP request from owned source bytes.
+///
+/// Unlike [`parse_request`], this does not copy the source.
+pub fn parse_request_owned(src: Bytes) -> Result<Request, ParseError> {
+    parse_request_from_bytes(&src, 0)
+}
+
+/// Parses a single HTTP request line, eg. `GET /index.html HTTP/1.1`.
+///
+/// Unlike [`parse_request`], this does not require the headers or the empty line which
+/// terminates the request head. The line terminator is optional, and anything following
+/// it is ignored. The span of the request line includes the terminator if present.
+///
+/// Returns an error if the method, target and version are not all present.
+///
+/// # Example
+///
+/// ```
+/// use spansy::http::parse_request_line;
+///
+/// let line = parse_request_line(b"GET /index.html HTTP/1.1").unwrap();
+///
+/// assert_eq!(line.method.as_str(), "GET");
+/// assert_eq!(line.target.as_str(), "/index.html");
+/// ```
+pub fn parse_request_line(src: &[u8]) -> Result<RequestLine, ParseError> {
+    let src = Bytes::copy_from_slice(src);
+    let (line_end, line_len) = single_line(&src)
+        .ok_or_else(|| ParseError("request line is not terminated with CRLF".to_string()))?;
+
+    // httparse only parses complete heads, so the line is terminated with an empty
+    // header block. The line is a prefix of the buffer, so ranges within the buffer
+    // are also ranges within the source.
+    let mut head = src[..line_end].to_vec();
+    head.extend_from_slice(b"\r\n\r\n");
+
+    let mut request = httparse::Request::new(&mut []);
+    match request.parse(&head) {
+        Ok(httparse::Status::Complete(_)) => {}
+        Ok(httparse::Status::Partial) => {
+            return Err(ParseError(format!("incomplete request line: {:?}", src)))
+        }
+        Err(err) => return Err(ParseError(format!("invalid request line: {err}"))),
+    }
+
+    let method = request
+        .method
+        .ok_or_else(|| ParseError("method missing from request".to_string()))?;
+    let path = request
+        .path
+        .ok_or_else(|| ParseError("path missing from request".to_string()))?;
+
+    let method_range = find_range(&src, 0..line_end, method.as_bytes())
+        .ok_or_else(|| ParseError("method is not present in request line".to_string()))?;
+    let path_range = get_span_range(&head, path.as_bytes());
+
+    Ok(RequestLine {
+        span: Span::new_str(&src, 0..line_len),
+        method: Method(Span::new_str(&src, method_range)),
+        target: Target(Span::new_str(&src, path_range)),
+    })
+}
+
+/// Parses the head of an HTTP request, ie. the request line and headers.
+///
+/// Unlike [`parse_request`], this does not determine the length of the body from the
+/// `Content-Length` and `Transfer-Encoding` headers, so it accepts requests whose
+/// framing is ambiguous, eg. with conflicting `Content-Length` values. Use
+/// [`Request::body_length_ambiguity`] to detect such requests. The returned request has
+/// no body, and its span ends with the empty line which terminates the head.
+///
+/// # Example
+///
+/// ```
+/// use spansy::http::{parse_request, parse_request_head};
+///
+/// let src = b"POST / HTTP/1.1\r\nContent-Length: 1\r\nContent-Length: 2\r\n\r\nab";
+///
+/// assert!(parse_request(src).is_err());
+///
+/// let req = parse_request_head(src).unwrap();
+///
+/// assert!(req.body_length_ambiguity().is_some());
+/// ```
+pub fn parse_request_head(src: &[u8]) -> Result<Request, ParseError> {
+    parse_request_head_from_bytes(&Bytes::copy_from_slice(src), 0)
 }
 
 /// Parses an HTTP request from a `Bytes` buffer starting from the `offset`.
 pub(crate) fn parse_request_from_bytes(src: &Bytes, offset: usize) -> Result<Request, ParseError> {
+    let mut request = parse_request_head_from_bytes(src, offset)?;
+    let head_end = offset + request.span.len();
+
+    let body_len = request_body_len(&request)?;
+
+    if body_len > 0 {
+        let range = head_end..head_end + body_len;
+
+        if range.end > src.len() {
+            return Err(ParseError(format!(
+                "body range {}..{} exceeds source {}",
+                range.start,
+                range.end,
+                src.len()
+            )));
+        }
+
+        request.span = Span::new_bytes(src, offset..range.end);
+
+        request.body = Some(Body {
+            span: Span::new_bytes(src, range),
+        });
+    }
+
+    Ok(request)
+}
+
+/// Parses the head of an HTTP request from a `Bytes` buffer starting from the `offset`.
+fn parse_request_head_from_bytes(src: &Bytes, offset: usize) -> Result<Request, ParseError> {
     let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
 
     let (method, path, head_end) = {
@@ -28,7 +159,7 @@ pub(crate) fn parse_request_from_bytes(src: &Bytes, offset: usize) -> Result<Req
             Ok(httparse::Status::Partial) => {
                 return Err(ParseError(format!("incomplete request: {:?}", src)))
             }
-            Err(err) => return Err(ParseError(err.to_string())),
+            Err(err) => return Err(header_error(src, offset, err)),
         };
 
         let method = request
@@ -42,82 +173,195 @@ pub(crate) fn parse_request_from_bytes(src: &Bytes, offset: usize) -> Result<Req
         (method, path, head_end)
     };
 
-    let request_line_end = src[offset..]
-        .windows(2)
-        .position(|w| w == b"\r\n")
-        .expect("request line is terminated with CRLF");
-    let request_line_range = offset..offset + request_line_end + 2;
+    let (_, request_line_len) = find_line_end(&src[offset..], false)
+        .ok_or_else(|| ParseError("request line is not terminated with CRLF".to_string()))?;
+    let request_line_range = offset..offset + request_line_len;
 
     let headers = headers
         .iter()
         .take_while(|h| *h != &httparse::EMPTY_HEADER)
-        .map(|header| from_header(src, header))
-        .collect();
+        .map(|header| from_header(src, header, ParseOptions::default()))
+        .collect::<Result<_, _>>()?;
 
     // httparse allocates a new buffer to store the method for performance reasons,
-    // so we have to search for the span in the source. This is quick as the method
-    // is at the front.
-    let method = src[offset..]
-        .windows(method.len())
-        .find(|w| *w == method.as_bytes())
-        .expect("method is present");
-
-    let mut request = Request {
-        span: Span::new_bytes(src.clone(), offset..head_end),
+    // so we have to search for the span in the source. The search is anchored to the
+    // request line, where the method is the first token.
+    let method_range = find_range(src, request_line_range.clone(), method.as_bytes())
+        .ok_or_else(|| ParseError("method is not present in request line".to_string()))?;
+
+    Ok(Request {
+        span: Span::new_bytes(src, offset..head_end),
         request: RequestLine {
-            span: Span::new_str(src.clone(), request_line_range),
-            method: Method(Span::new_str(src.clone(), get_span_range(src, method))),
-            target: Target(Span::new_from_str(src.clone(), path)),
+            span: Span::new_str(src, request_line_range),
+            method: Method(Span::new_str(src, method_range)),
+            target: Target(Span::new_from_str(src, path)),
         },
         headers,
         body: None,
-    };
+        source: Source(src.clone()),
+    })
+}
 
-    let body_len = request_body_len(&request)?;
+/// Parses an HTTP response.
+pub fn parse_response(src: &[u8]) -> Result<Response, ParseError> {
+    parse_response_owned(Bytes::copy_from_slice(src))
+}
 
-    if body_len > 0 {
-        let range = head_end..head_end + body_len;
+/// Parses an HTTP response from owned source bytes.
+///
+/// Unlike [`parse_response`], this does not copy the source.
+pub fn parse_response_owned(src: Bytes) -> Result<Response, ParseError> {
+    parse_response_from_bytes(&src, 0, ParseOptions::default())
+}
 
-        if range.end > src.len() {
-            return Err(ParseError(format!(
-                "body range {}..{} exceeds source {}",
-                range.start,
-                range.end,
-                src.len()
-            )));
-        }
+/// Parses an HTTP response to a request with the given method.
+///
+/// Whether a response has a body depends on the method of the request: a response to a
+/// HEAD request never has a body, even if it has a `Content-Length` header. The method
+/// is case-sensitive. [`parse_response`] assumes the method is not HEAD.
+///
+/// # Example
+///
+/// ```
+/// use spansy::http::parse_response_for_method;
+///
+/// let src = b"HTTP/1.1 200 OK\r\nContent-Length: 1024\r\n\r\n";
+///
+/// let res = parse_response_for_method(src, "HEAD").unwrap();
+///
+/// assert!(res.body.is_none());
+/// assert!(parse_response_for_method(src, "GET").is_err());
+/// ```
+pub fn parse_response_for_method(src: &[u8], method: &str) -> Result<Response, ParseError> {
+    parse_response_from_bytes(
+        &Bytes::copy_from_slice(src),
+        0,
+        ParseOptions {
+            head: method == "HEAD",
+            ..Default::default()
+        },
+    )
+}
 
-        request.span = Span::new_bytes(src.clone(), offset..range.end);
+/// Parses an HTTP response, accepting bare LF line terminators.
+///
+/// RFC 9112 requires the status line and header lines to be terminated with CRLF, which
+/// [`parse_response`] enforces. Some legacy servers terminate lines with a bare LF
+/// instead, which this accepts. The spans of the status line and headers include the
+/// line terminator as it appears in the source.
+pub fn parse_response_lenient(src: &[u8]) -> Result<Response, ParseError> {
+    parse_response_from_bytes(
+        &Bytes::copy_from_slice(src),
+        0,
+        ParseOptions {
+            lenient: true,
+            ..Default::default()
+        },
+    )
+}
 
-        request.body = Some(Body {
-            span: Span::new_bytes(src.clone(), range),
-        });
+/// Parses an HTTP response, joining header values which are folded across multiple lines.
+///
+/// Folding a header value by starting a line with whitespace (obs-fold) is deprecated by
+/// RFC 9112, and is rejected by [`parse_response`]. With this function the span of a
+/// folded value covers each of its lines, excluding the line breaks but including the
+/// leading whitespace of each continuation line.
+///
+/// # Example
+///
+/// ```
+/// use spansy::http::{parse_response, parse_response_folded};
+///
+/// let src = b"HTTP/1.1 200 OK\r\nX-Folded: hello\r\n  world\r\nContent-Length: 0\r\n\r\n";
+///
+/// assert!(parse_response(src).is_err());
+///
+/// let res = parse_response_folded(src).unwrap();
+///
+/// assert_eq!(res.headers[0].value.as_bytes(), b"hello  world");
+/// ```
+pub fn parse_response_folded(src: &[u8]) -> Result<Response, ParseError> {
+    parse_response_from_bytes(
+        &Bytes::copy_from_slice(src),
+        0,
+        ParseOptions {
+            fold: true,
+            ..Default::default()
+        },
+    )
+}
+
+/// Parses a single HTTP status line, eg. `HTTP/1.1 200 OK`.
+///
+/// Unlike [`parse_response`], this does not require the headers or the empty line which
+/// terminates the response head. The line terminator is optional, and anything following
+/// it is ignored. The span of the status line includes the terminator if present.
+///
+/// The reason phrase is optional, in which case its span is empty.
+///
+/// # Example
+///
+/// ```
+/// use spansy::http::parse_status_line;
+///
+/// let status = parse_status_line(b"HTTP/1.1 404 Not Found").unwrap();
+///
+/// assert_eq!(status.code.as_str(), "404");
+/// assert_eq!(status.reason.as_str(), "Not Found");
+/// ```
+pub fn parse_status_line(src: &[u8]) -> Result<Status, ParseError> {
+    let src = Bytes::copy_from_slice(src);
+    let (line_end, line_len) = single_line(&src)
+        .ok_or_else(|| ParseError("status line is not terminated with CRLF".to_string()))?;
+
+    // httparse only parses complete heads, so the line is terminated with an empty
+    // header block.
+    let mut head = src[..line_end].to_vec();
+    head.extend_from_slice(b"\r\n\r\n");
+
+    let mut response = httparse::Response::new(&mut []);
+    match response.parse(&head) {
+        Ok(httparse::Status::Complete(_)) => {}
+        Ok(httparse::Status::Partial) => {
+            return Err(ParseError(format!("incomplete status line: {:?}", src)))
+        }
+        Err(err) => return Err(ParseError(format!("invalid status line: {err}"))),
     }
 
-    Ok(request)
-}
+    let code = response
+        .code
+        .ok_or_else(|| ParseError("code missing from response".to_string()))?
+        .to_string();
 
-/// Parses an HTTP response.
-pub fn parse_response(src: &[u8]) -> Result<Response, ParseError> {
-    parse_response_from_bytes(&Bytes::copy_from_slice(src), 0)
+    let (code_range, reason_range) = find_code_and_reason(&src, 0..line_end, &code)?;
+
+    Ok(Status {
+        span: Span::new_str(&src, 0..line_len),
+        code: Code(Span::new_str(&src, code_range)),
+        reason: Reason(Span::new_str(&src, reason_range)),
+    })
 }
 
 /// Parses an HTTP response from a `Bytes` buffer starting from the `offset`.
 pub(crate) fn parse_response_from_bytes(
     src: &Bytes,
     offset: usize,
+    options: ParseOptions,
 ) -> Result<Response, ParseError> {
     let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
 
-    let (reason, code, head_end) = {
+    let (code, head_end) = {
         let mut response = httparse::Response::new(&mut headers);
 
-        let head_end = match response.parse(&src[offset..]) {
+        let head_end = match httparse::ParserConfig::default()
+            .allow_obsolete_multiline_headers_in_responses(options.fold)
+            .parse_response(&mut response, &src[offset..])
+        {
             Ok(httparse::Status::Complete(head_end)) => head_end + offset,
             Ok(httparse::Status::Partial) => {
                 return Err(ParseError(format!("incomplete response: {:?}", src)))
             }
-            Err(err) => return Err(ParseError(err.to_string())),
+            Err(err) => return Err(header_error(src, offset, err)),
         };
 
         let code = response
@@ -125,43 +369,39 @@ pub(crate) fn parse_response_from_bytes(
             .ok_or_else(|| ParseError("code missing from response".to_string()))
             .map(|c| c.to_string())?;
 
-        let reason = response
-            .reason
-            .ok_or_else(|| ParseError("reason missing from response".to_string()))?;
-
-        (reason, code, head_end)
+        (code, head_end)
     };
 
-    let status_line_end = src[offset..]
-        .windows(2)
-        .position(|w| w == b"\r\n")
-        .expect("status line is terminated with CRLF");
-    let status_line_range = offset..offset + status_line_end + 2;
+    let (status_line_end, status_line_len) = find_line_end(&src[offset..], options.lenient)
+        .ok_or_else(|| ParseError("status line is not terminated with CRLF".to_string()))?;
+    let status_line_range = offset..offset + status_line_len;
 
     let headers = headers
         .iter()
         .take_while(|h| *h != &httparse::EMPTY_HEADER)
-        .map(|header| from_header(src, header))
-        .collect();
+        .map(|header| from_header(src, header, options))
+        .collect::<Result<_, _>>()?;
 
-    // httparse doesn't preserve the response code span, so we find it.
-    let code = src[offset..]
-        .windows(3)
-        .find(|w| *w == code.as_bytes())
-        .expect("code is present");
+    let (code_range, reason_range) =
+        find_code_and_reason(src, offset..offset + status_line_end, &code)?;
 
     let mut response = Response {
-        span: Span::new_bytes(src.clone(), offset..head_end),
+        span: Span::new_bytes(src, offset..head_end),
         status: Status {
-            span: Span::new_str(src.clone(), status_line_range),
-            code: Code(Span::new_str(src.clone(), get_span_range(src, code))),
-            reason: Reason(Span::new_from_str(src.clone(), reason)),
+            span: Span::new_str(src, status_line_range),
+            code: Code(Span::new_str(src, code_range)),
+            reason: Reason(Span::new_str(src, reason_range)),
         },
         headers,
         body: None,
+        source: Source(src.clone()),
     };
 
-    let body_len = response_body_len(&response)?;
+    let body_len = if options.head {
+        0
+    } else {
+        response_body_len(&response)?
+    };
 
     if body_len > 0 {
         let range = head_end..head_end + body_len;
@@ -175,34 +415,183 @@ pub(crate) fn parse_response_from_bytes(
             )));
         }
 
-        response.span = Span::new_bytes(src.clone(), offset..range.end);
+        response.span = Span::new_bytes(src, offset..range.end);
 
         response.body = Some(Body {
-            span: Span::new_bytes(src.clone(), range),
+            span: Span::new_bytes(src, range),
         });
     }
 
     Ok(response)
 }
 
+/// Returns the ranges of the response code and reason phrase within the status line,
+/// given the range of the status line excluding its terminator.
+fn find_code_and_reason(
+    src: &[u8],
+    status_line: Range<usize>,
+    code: &str,
+) -> Result<(Range<usize>, Range<usize>), ParseError> {
+    // httparse doesn't preserve the response code span, so we find it. The search is
+    // anchored to the status line, where the code is the first three digit sequence
+    // following the version.
+    let code_range = find_range(src, status_line.clone(), code.as_bytes())
+        .ok_or_else(|| ParseError("code is not present in status line".to_string()))?;
+
+    // httparse returns a static empty string if the reason phrase is missing, so we
+    // find it as the remainder of the status line following the code and a single SP.
+    let reason_start = if src.get(code_range.end) == Some(&b' ') {
+        code_range.end + 1
+    } else {
+        code_range.end
+    };
+    let reason_range = reason_start..status_line.end;
+    std::str::from_utf8(&src[reason_range.clone()])?;
+
+    Ok((code_range, reason_range))
+}
+
+/// Returns the length of the first line excluding and including its terminator.
+///
+/// Lines are terminated with CRLF, or if `lenient` is `true` also with a bare LF.
+/// Returns `None` if the first line is not terminated.
+fn find_line_end(src: &[u8], lenient: bool) -> Option<(usize, usize)> {
+    let lf_idx = src.iter().position(|b| *b == b'\n')?;
+
+    if lf_idx > 0 && src[lf_idx - 1] == b'\r' {
+        Some((lf_idx - 1, lf_idx + 1))
+    } else if lenient {
+        Some((lf_idx, lf_idx + 1))
+    } else {
+        None
+    }
+}
+
+/// Returns the length of a single line excluding and including its terminator.
+///
+/// Unlike [`find_line_end`] the line does not need to be terminated, in which case it
+/// spans the entire source. Returns `None` if the line is terminated with a bare LF.
+fn single_line(src: &[u8]) -> Option<(usize, usize)> {
+    if src.contains(&b'\n') {
+        find_line_end(src, false)
+    } else {
+        Some((src.len(), src.len()))
+    }
+}
+
+/// Converts an error from httparse into a `ParseError`.
+///
+/// httparse rejects header lines folded across multiple lines as an invalid header
+/// name, so these are detected to return a more descriptive error.
+fn header_error(src: &Bytes, offset: usize, err: httparse::Error) -> ParseError {
+    match find_obs_fold(&src[offset..]) {
+        Some(idx) if err == httparse::Error::HeaderName => ParseError(format!(
+            "obsolete line folding in header at index {} is not supported",
+            offset + idx
+        )),
+        _ => ParseError(err.to_string()),
+    }
+}
+
+/// Returns the index of the first header line which continues the previous line, if any.
+///
+/// Such a line starts with whitespace and is an obsolete line folding (obs-fold), see
+/// RFC 9112, section 5.2.
+fn find_obs_fold(src: &[u8]) -> Option<usize> {
+    // Skip the start line.
+    let mut pos = src.iter().position(|b| *b == b'\n')? + 1;
+    while pos < src.len() {
+        match src[pos] {
+            b' ' | b'\t' => return Some(pos),
+            // The head is terminated by an empty line.
+            b'\r' | b'\n' => return None,
+            _ => pos += src[pos..].iter().position(|b| *b == b'\n')? + 1,
+        }
+    }
+
+    None
+}
+
 /// Converts a `httparse::Header` to a `Header`.
-fn from_header(src: &Bytes, header: &httparse::Header) -> Header {
+fn from_header(
+    src: &Bytes,
+    header: &httparse::Header,
+    options: ParseOptions,
+) -> Result<Header, ParseError> {
     let name_range = get_span_range(src, header.name.as_bytes());
     let value_range = get_span_range(src, header.value);
 
-    let crlf_idx = src[value_range.end..]
-        .windows(2)
-        .position(|b| b == b"\r\n")
-        .expect("CRLF is present in a valid header");
+    let (_, line_len) =
+        find_line_end(&src[value_range.end..], options.lenient).ok_or_else(|| {
+            ParseError(format!(
+                "header \"{}\" is not terminated with CRLF",
+                header.name
+            ))
+        })?;
+
+    // Capture the entire header including trailing whitespace and the line terminator.
+    let header_range = name_range.start..value_range.end + line_len;
+
+    // A folded value spans multiple lines, in which case the value excludes the line
+    // breaks but retains the leading whitespace of each continuation line.
+    let value = if options.fold && header.value.contains(&b'\n') {
+        let mut ranges = Vec::new();
+        let mut start = value_range.start;
+        for line in header.value.split(|b| *b == b'\n') {
+            let len = line.strip_suffix(b"\r").unwrap_or(line).len();
+            if len > 0 {
+                ranges.push(start..start + len);
+            }
+            start += line.len() + 1;
+        }
+
+        Span::from_ranges(src.clone(), ranges)
+    } else {
+        Span::new_bytes(src, value_range)
+    };
+
+    Ok(Header {
+        span: Span::new_bytes(src, header_range),
+        name: HeaderName(Span::new_str(src, name_range)),
+        value: HeaderValue(value),
+    })
+}
 
-    // Capture the entire header including trailing whitespace and the CRLF.
-    let header_range = name_range.start..value_range.end + crlf_idx + 2;
+/// Returns the value of the given `Content-Length` headers, or `None` if there are none.
+///
+/// Repeated headers with identical values are accepted, but differing values are an
+/// error as the message length can not be determined, see RFC 9110, section 8.6.
+pub(crate) fn content_length<'a>(
+    headers: impl Iterator<Item = &'a Header>,
+) -> Result<Option<usize>, ParseError> {
+    let mut len = None;
+    for h in headers {
+        // The value is a decimal number, which is commonly padded with whitespace.
+        let value = h.value.as_bytes();
+        let value = &value[trim_ows(value)];
 
-    Header {
-        span: Span::new_bytes(src.clone(), header_range),
-        name: HeaderName(Span::new_str(src.clone(), name_range)),
-        value: HeaderValue(Span::new_bytes(src.clone(), value_range)),
+        if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+            return Err(ParseError(format!(
+                "invalid Content-Length value: \"{}\"",
+                String::from_utf8_lossy(h.value.as_bytes())
+            )));
+        }
+
+        let value = std::str::from_utf8(value)?
+            .parse::<usize>()
+            .map_err(|err| ParseError(format!("failed to parse Content-Length value: {err}")))?;
+
+        match len {
+            Some(len) if len != value => {
+                return Err(ParseError(format!(
+                    "conflicting Content-Length values: {len} and {value}"
+                )))
+            }
+            _ => len = Some(value),
+        }
     }
+
+    Ok(len)
 }
 
 /// Calculates the length of the request body according to RFC 9112, section 6.
@@ -220,12 +609,10 @@ fn request_body_len(request: &Request) -> Result<usize, ParseError> {
         Err(ParseError(
             "Transfer-Encoding not supported yet".to_string(),
         ))
-    } else if let Some(h) = request.headers_with_name("Content-Length").next() {
+    } else if let Some(len) = content_length(request.headers_with_name("Content-Length"))? {
         // If a valid Content-Length header field is present without Transfer-Encoding, its decimal value
         // defines the expected message body length in octets.
-        std::str::from_utf8(h.value.0.as_bytes())?
-            .parse::<usize>()
-            .map_err(|err| ParseError(format!("failed to parse Content-Length value: {err}")))
+        Ok(len)
     } else {
         // If this is a request message and none of the above are true, then the message body length is zero
         Ok(0)
@@ -237,12 +624,14 @@ fn response_body_len(response: &Response) -> Result<usize, ParseError> {
     // Any response to a HEAD request and any response with a 1xx (Informational), 204 (No Content), or 304 (Not Modified)
     // status code is always terminated by the first empty line after the header fields, regardless of the header fields
     // present in the message, and thus cannot contain a message body or trailer section.
+    //
+    // Responses to HEAD requests are handled by the caller, as the request method is not known here.
     match response
         .status
         .code
         .as_str()
         .parse::<usize>()
-        .expect("code is valid utf-8")
+        .map_err(|err| ParseError(format!("failed to parse response code: {err}")))?
     {
         100..=199 | 204 | 304 => return Ok(0),
         _ => {}
@@ -256,12 +645,10 @@ fn response_body_len(response: &Response) -> Result<usize, ParseError> {
         Err(ParseError(
             "Transfer-Encoding not supported yet".to_string(),
         ))
-    } else if let Some(h) = response.headers_with_name("Content-Length").next() {
+    } else if let Some(len) = content_length(response.headers_with_name("Content-Length"))? {
         // If a valid Content-Length header field is present without Transfer-Encoding, its decimal value
         // defines the expected message body length in octets.
-        std::str::from_utf8(h.value.0.as_bytes())?
-            .parse::<usize>()
-            .map_err(|err| ParseError(format!("failed to parse Content-Length value: {err}")))
+        Ok(len)
     } else {
         // If this is a response message and none of the above are true, then there is no way to
         // determine the length of the message body except by reading it until the connection is closed.
@@ -275,7 +662,9 @@ fn response_body_len(response: &Response) -> Result<usize, ParseError> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Spanned;
+    use utils::range::RangeSet;
+
+    use crate::{http::MethodKind, Spanned};
 
     use super::*;
 
@@ -376,6 +765,354 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_request_line() {
+        let line = parse_request_line(b"POST /a?b=c HTTP/1.1\r\nHost: x").unwrap();
+
+        assert_eq!(line.span, 0..22);
+        assert_eq!(line.method.0, 0..4);
+        assert_eq!(line.target.0, 5..11);
+        assert_eq!(line.target.as_str(), "/a?b=c");
+        assert_eq!(line.method.kind(), MethodKind::Post);
+
+        let line = parse_request_line(b"GET / HTTP/1.0").unwrap();
+        assert_eq!(line.span, "GET / HTTP/1.0");
+        assert_eq!(line.method.kind(), MethodKind::Get);
+
+        assert!(parse_request_line(b"GET /").is_err());
+        assert!(parse_request_line(b"GET / HTTP/1.1 extra").is_err());
+        assert!(parse_request_line(b"GET / HTTP/1.1\n").is_err());
+        assert!(parse_request_line(b"").is_err());
+    }
+
+    #[test]
+    fn test_parse_request_no_headers() {
+        let src = b"GET / HTTP/1.1\r\n\r\n";
+        let req = parse_request(src).unwrap();
+
+        assert!(req.headers.is_empty());
+        assert!(req.body.is_none());
+        assert_eq!(req.span(), src.as_slice());
+        assert_eq!(req.request.span, 0..16);
+        assert_eq!(req.head_span(), src.as_slice());
+        assert_eq!(req.headers_span(), b"\r\n".as_slice());
+        assert_eq!(req.headers_span().indices(), &(16..18));
+        assert_eq!(req.without_data(), RangeSet::from(vec![0..4, 5..18]));
+
+        let req = parse_request(b"GET / HTTP/1.1\r\n\r\nGET /b HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(req.span(), b"GET / HTTP/1.1\r\n\r\n".as_slice());
+
+        let mut req = req.compact();
+        req.offset(4);
+        assert_eq!(req.headers_span().indices(), &(20..22));
+    }
+
+    #[test]
+    fn test_parse_response_no_headers() {
+        let src = b"HTTP/1.1 204 No Content\r\n\r\n";
+        let res = parse_response(src).unwrap();
+
+        assert!(res.headers.is_empty());
+        assert!(res.body.is_none());
+        assert_eq!(res.span(), src.as_slice());
+        assert_eq!(res.status.span, 0..25);
+        assert_eq!(res.head_span(), src.as_slice());
+        assert_eq!(res.headers_span(), b"\r\n".as_slice());
+        assert_eq!(res.headers_span().indices(), &(25..27));
+        assert_eq!(res.without_data(), RangeSet::from(0..27));
+
+        let res = parse_response_for_method(b"HTTP/1.1 200 OK\r\n\r\n", "HEAD").unwrap();
+        assert!(res.headers.is_empty());
+        assert_eq!(res.headers_span(), b"\r\n".as_slice());
+    }
+
+    #[test]
+    fn test_parse_method_kind() {
+        let kinds = [
+            ("GET", MethodKind::Get),
+            ("HEAD", MethodKind::Head),
+            ("POST", MethodKind::Post),
+            ("PUT", MethodKind::Put),
+            ("DELETE", MethodKind::Delete),
+            ("OPTIONS", MethodKind::Options),
+            ("TRACE", MethodKind::Trace),
+            ("PATCH", MethodKind::Patch),
+            ("PROPFIND", MethodKind::Other),
+            ("get", MethodKind::Other),
+        ];
+
+        for (method, kind) in kinds {
+            let src = format!("{method} / HTTP/1.1\r\n\r\n");
+            let req = parse_request(src.as_bytes()).unwrap();
+            assert_eq!(req.request.method.kind(), kind, "{method}");
+        }
+
+        let req = parse_request(b"CONNECT example.com:443 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(req.request.method.kind(), MethodKind::Connect);
+    }
+
+    #[test]
+    fn test_parse_status_line() {
+        let status = parse_status_line(b"HTTP/1.1 200 OK\r\nContent-Length: 0").unwrap();
+
+        assert_eq!(status.span, 0..17);
+        assert_eq!(status.code.0, 9..12);
+        assert_eq!(status.reason.0, 13..15);
+
+        let status = parse_status_line(b"HTTP/1.1 204 ").unwrap();
+        assert!(!status.has_reason());
+        assert_eq!(status.span, "HTTP/1.1 204 ");
+
+        let status = parse_status_line(b"HTTP/1.0 500 Internal Server Error").unwrap();
+        assert_eq!(status.reason.as_str(), "Internal Server Error");
+
+        assert!(parse_status_line(b"HTTP/1.1").is_err());
+        assert!(parse_status_line(b"HTTP/1.1 20 OK").is_err());
+        assert!(parse_status_line(b"HTTP/1.1 200 OK\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_response_missing_reason() {
+        let res = parse_response(b"HTTP/1.1 200 \r\nContent-Length: 0\r\n\r\n").unwrap();
+
+        assert!(!res.status.has_reason());
+        assert_eq!(res.status.reason.as_str(), "");
+        assert!(res.status.reason.span().is_empty());
+        assert_eq!(res.status.span(), "HTTP/1.1 200 \r\n");
+
+        let res = parse_response(b"HTTP/1.1 204\r\n\r\n").unwrap();
+
+        assert!(!res.status.has_reason());
+        assert_eq!(res.status.code.as_str(), "204");
+        assert!(res.status.reason.span().is_empty());
+
+        let res = parse_response(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n").unwrap();
+
+        assert!(res.status.has_reason());
+        assert_eq!(res.status.reason.as_str(), "Not Found");
+        assert_eq!(res.status.reason.span().indices(), &(13..22));
+    }
+
+    #[test]
+    fn test_parse_content_length_duplicates() {
+        let req =
+            parse_request(b"POST / HTTP/1.1\r\nContent-Length: 4\r\nContent-Length: 4\r\n\r\nbody")
+                .unwrap();
+        assert_eq!(req.body.unwrap().span(), b"body".as_slice());
+
+        let res = parse_response(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\ncontent-length: 4\r\n\r\nbody",
+        )
+        .unwrap();
+        assert_eq!(res.body.unwrap().span(), b"body".as_slice());
+    }
+
+    #[test]
+    fn test_parse_content_length_value() {
+        let parse = |value: &str| {
+            parse_request(
+                format!("POST / HTTP/1.1\r\nContent-Length:{value}\r\n\r\n0123456789abcdef")
+                    .as_bytes(),
+            )
+            .map(|req| req.body.map_or(0, |body| body.span().len()))
+        };
+
+        assert_eq!(parse(" 007").unwrap(), 7);
+        assert_eq!(parse("  12 ").unwrap(), 12);
+        assert_eq!(parse("\t0").unwrap(), 0);
+        assert_eq!(
+            parse(" 1_000").unwrap_err().to_string(),
+            "parsing error: invalid Content-Length value: \"1_000\""
+        );
+        assert!(parse(" +12").is_err());
+        assert!(parse(" 1 2").is_err());
+        assert!(parse(" ").is_err());
+        assert!(parse(" 99999999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_parse_content_length_conflict() {
+        assert_eq!(
+            parse_request(
+                b"POST / HTTP/1.1\r\nContent-Length: 4\r\nContent-Length: 2\r\n\r\nbody",
+            )
+            .unwrap_err()
+            .to_string(),
+            "parsing error: conflicting Content-Length values: 4 and 2"
+        );
+        assert!(parse_response(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nContent-Length: 4\r\n\r\nbody",
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_response_lenient() {
+        let src = b"HTTP/1.0 200 OK\nServer: legacy\r\nContent-Length: 4\n\nbody";
+
+        let res = parse_response_lenient(src).unwrap();
+
+        assert_eq!(res.span(), src.as_slice());
+        assert_eq!(res.status.span(), "HTTP/1.0 200 OK\n");
+        assert_eq!(res.status.reason.as_str(), "OK");
+        assert_eq!(res.headers[0].span(), b"Server: legacy\r\n".as_slice());
+        assert_eq!(res.headers[0].value.span(), b"legacy".as_slice());
+        assert_eq!(res.headers[1].span(), b"Content-Length: 4\n".as_slice());
+        assert_eq!(res.headers[1].span(), 32..50);
+        assert_eq!(res.body.unwrap().span(), b"body".as_slice());
+
+        let res = parse_response_lenient(b"HTTP/1.0 204\n\n").unwrap();
+        assert!(!res.status.has_reason());
+        assert!(res.headers.is_empty());
+
+        // CRLF is still accepted.
+        assert_eq!(
+            parse_response_lenient(TEST_RESPONSE).unwrap(),
+            parse_response(TEST_RESPONSE).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_no_panic() {
+        let inputs = [
+            TEST_REQUEST,
+            TEST_RESPONSE,
+            TEST_REQUEST2,
+            TEST_RESPONSE2,
+            b"GET / HTTP/1.1\nHost: a\n\n".as_slice(),
+            b"HTTP/1.1 200 OK\nContent-Length: 0\n\n".as_slice(),
+            b"HTTP/1.1 200\r\nA: b\nC: d\r\n\r\n".as_slice(),
+            b"GET  /  HTTP/1.1\r\n\tX:  y \r\n\r\n".as_slice(),
+            b"HTTP/1.1  200  OK\r\n\r\n".as_slice(),
+        ];
+
+        for input in inputs {
+            for len in 0..=input.len() {
+                let input = &input[..len];
+                let _ = parse_request(input);
+                let _ = parse_response(input);
+                let _ = parse_response_lenient(input);
+
+                let stripped = input
+                    .iter()
+                    .copied()
+                    .filter(|b| *b != b'\r')
+                    .collect::<Vec<_>>();
+                let _ = parse_request(&stripped);
+                let _ = parse_response(&stripped);
+                let _ = parse_response_lenient(&stripped);
+            }
+        }
+
+        assert_eq!(
+            parse_request(b"GET / HTTP/1.1\nHost: a\n\n")
+                .unwrap_err()
+                .to_string(),
+            "parsing error: request line is not terminated with CRLF"
+        );
+        assert_eq!(
+            parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 0\n\r\n")
+                .unwrap_err()
+                .to_string(),
+            "parsing error: header \"Content-Length\" is not terminated with CRLF"
+        );
+    }
+
+    #[test]
+    fn test_parse_request_method_anchored() {
+        let src =
+            b"POST /submit HTTP/1.1\r\nX-Original-Method: POST\r\nContent-Length: 4\r\n\r\nPOST";
+
+        let req = parse_request(src).unwrap();
+
+        assert_eq!(req.request.method.as_str(), "POST");
+        assert_eq!(req.request.method.span(), 0..4);
+
+        let mut reqs = crate::http::Requests::new_from_slice(
+            b"GET /GET HTTP/1.1\r\n\r\nGET /x HTTP/1.1\r\nX: GET\r\n\r\n",
+        );
+        assert_eq!(reqs.next().unwrap().unwrap().request.method.span(), 0..3);
+        assert_eq!(reqs.next().unwrap().unwrap().request.method.span(), 21..24);
+    }
+
+    #[test]
+    fn test_parse_response_code_anchored() {
+        let src =
+            b"HTTP/1.1 200 Code 200 OK\r\nX-Upstream-Status: 200\r\nContent-Length: 3\r\n\r\n200";
+
+        let res = parse_response(src).unwrap();
+
+        assert_eq!(res.status.code.as_str(), "200");
+        assert_eq!(res.status.code.span(), 9..12);
+        assert_eq!(res.status.reason.as_str(), "Code 200 OK");
+
+        let mut resps = crate::http::Responses::new_from_slice(
+            b"HTTP/1.1 204 204\r\n\r\nHTTP/1.1 204 No Content\r\n\r\n",
+        );
+        assert_eq!(resps.next().unwrap().unwrap().status.code.span(), 9..12);
+        assert_eq!(resps.next().unwrap().unwrap().status.code.span(), 29..32);
+    }
+
+    #[test]
+    fn test_parse_obs_fold() {
+        let src = b"HTTP/1.1 200 OK\r\nX-Folded: a\r\n b\r\n\tc\r\nContent-Length: 0\r\n\r\n";
+
+        assert_eq!(
+            parse_response(src).unwrap_err().to_string(),
+            "parsing error: obsolete line folding in header at index 30 is not supported"
+        );
+        assert_eq!(
+            parse_request(b"GET / HTTP/1.1\r\nX-Folded: a\r\n b\r\n\r\n")
+                .unwrap_err()
+                .to_string(),
+            "parsing error: obsolete line folding in header at index 29 is not supported"
+        );
+
+        let res = parse_response_folded(src).unwrap();
+
+        let header = &res.headers[0];
+        assert_eq!(header.span(), b"X-Folded: a\r\n b\r\n\tc\r\n".as_slice());
+        assert_eq!(header.value.as_bytes(), b"a b\tc");
+        assert_eq!(
+            header.value.span().indices(),
+            &RangeSet::from([27..28, 30..32, 34..36])
+        );
+        assert_eq!(res.headers[1].value.as_bytes(), b"0");
+        assert!(res.body.is_none());
+    }
+
+    #[test]
+    fn test_parse_response_for_method() {
+        let src = b"HTTP/1.1 200 OK\r\nContent-Length: 12\r\n\r\nHello World!";
+
+        let res = parse_response_for_method(src, "HEAD").unwrap();
+        assert!(res.body.is_none());
+        assert_eq!(
+            res.span(),
+            b"HTTP/1.1 200 OK\r\nContent-Length: 12\r\n\r\n".as_slice()
+        );
+
+        let res = parse_response_for_method(src, "GET").unwrap();
+        assert_eq!(res, parse_response(src).unwrap());
+        assert_eq!(res.body.unwrap().span(), b"Hello World!".as_slice());
+    }
+
+    #[test]
+    fn test_parse_owned() {
+        let src = Bytes::from_static(TEST_REQUEST);
+        let req = parse_request_owned(src.clone()).unwrap();
+
+        // The spans are sliced from the source without copying.
+        assert_eq!(req.span().data().as_ptr(), src.as_ptr());
+        assert_eq!(req.span(), TEST_REQUEST);
+
+        let src = Bytes::from_static(TEST_RESPONSE);
+        let res = parse_response_owned(src.clone()).unwrap();
+
+        assert_eq!(res.span().data().as_ptr(), src.as_ptr());
+        assert_eq!(res.span(), TEST_RESPONSE);
+    }
+
     // Make sure the first request is not parsed.
     #[test]
     fn test_parse_request_from_bytes() {
@@ -410,7 +1147,9 @@ mod tests {
         response.extend(TEST_RESPONSE2);
         response.extend(TEST_RESPONSE);
         let response = Bytes::copy_from_slice(&response);
-        let res = parse_response_from_bytes(&response, TEST_RESPONSE2.len()).unwrap();
+        let res =
+            parse_response_from_bytes(&response, TEST_RESPONSE2.len(), ParseOptions::default())
+                .unwrap();
 
         assert_eq!(res.span(), TEST_RESPONSE);
         assert_eq!(res.status.code.as_str(), "200");
@@ -1,6 +1,14 @@
+use std::ops::Range;
+
+use bytes::Bytes;
 use utils::range::{RangeDifference, RangeSet};
 
-use crate::{Span, Spanned};
+use super::span::content_length;
+use crate::{
+    helpers::{split_list, trim_ows},
+    json::{self, JsonValue},
+    ParseError, Source, Span, Spanned,
+};
 
 /// An HTTP header name.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -13,6 +21,69 @@ impl HeaderName {
         self.0.as_str()
     }
 
+    /// Returns the canonical form of the header name, eg. `Content-Type`.
+    ///
+    /// Each word separated by `-` is capitalized, and the remainder lowercased. Well-known
+    /// headers which do not follow this rule, such as `ETag` and `WWW-Authenticate`, use
+    /// their conventional casing. The name as it appears in the source is available via
+    /// [`HeaderName::as_str`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(b"GET / HTTP/1.1\r\ncontent-TYPE: text/plain\r\n\r\n").unwrap();
+    /// let name = &req.headers[0].name;
+    ///
+    /// assert_eq!(name.as_str(), "content-TYPE");
+    /// assert_eq!(name.to_canonical(), "Content-Type");
+    /// ```
+    pub fn to_canonical(&self) -> String {
+        /// Well-known names which are not in train case.
+        const NAMES: &[&str] = &[
+            "CDN-Loop",
+            "Content-ID",
+            "Content-MD5",
+            "DNT",
+            "DPR",
+            "ETag",
+            "Expect-CT",
+            "NEL",
+            "SourceMap",
+            "TE",
+            "WWW-Authenticate",
+            "X-DNS-Prefetch-Control",
+            "X-UA-Compatible",
+            "X-WebKit-CSP",
+            "X-XSS-Protection",
+        ];
+        /// Well-known words which are not in train case.
+        const WORDS: &[&str] = &["WebSocket"];
+
+        let name = self.as_str();
+        if let Some(canonical) = NAMES.iter().find(|n| n.eq_ignore_ascii_case(name)) {
+            return canonical.to_string();
+        }
+
+        let mut canonical = String::with_capacity(name.len());
+        for (idx, word) in name.split('-').enumerate() {
+            if idx > 0 {
+                canonical.push('-');
+            }
+            if let Some(known) = WORDS.iter().find(|w| w.eq_ignore_ascii_case(word)) {
+                canonical.push_str(known);
+                continue;
+            }
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                canonical.push(first.to_ascii_uppercase());
+                canonical.extend(chars.map(|c| c.to_ascii_lowercase()));
+            }
+        }
+        canonical
+    }
+
     /// Shifts the span range by the given offset.
     pub fn offset(&mut self, offset: usize) {
         self.0.offset(offset);
@@ -36,6 +107,14 @@ impl HeaderValue {
         self.0.as_bytes()
     }
 
+    /// Returns the header value as a string slice.
+    ///
+    /// Header values are usually ASCII, but may contain arbitrary bytes. Returns an error
+    /// if the value is not a valid UTF-8 string.
+    pub fn as_str(&self) -> Result<&str, ParseError> {
+        Ok(std::str::from_utf8(self.as_bytes())?)
+    }
+
     /// Shifts the span range by the given offset.
     pub fn offset(&mut self, offset: usize) {
         self.0.offset(offset);
@@ -67,12 +146,141 @@ impl Header {
         self.span.indices.difference(&self.value.span().indices)
     }
 
+    /// Returns `true` if the header value, with surrounding optional whitespace removed,
+    /// is equal to the given string ignoring ASCII case.
+    pub fn value_eq_ignore_case(&self, other: &str) -> bool {
+        let value = self.value.as_bytes();
+
+        value[trim_ows(value)].eq_ignore_ascii_case(other.as_bytes())
+    }
+
+    /// Returns an iterator over the elements of a comma-separated list header value.
+    ///
+    /// Each element has surrounding optional whitespace removed, and empty elements are
+    /// skipped. Commas within a quoted string do not split the value. Elements which are
+    /// not valid UTF-8 are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(b"GET / HTTP/1.1\r\nAccept-Encoding: gzip, deflate, br\r\n\r\n")
+    ///     .unwrap();
+    /// let header = req.headers_with_name("accept-encoding").next().unwrap();
+    ///
+    /// let values = header.list_values().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(values[1], "deflate");
+    /// assert_eq!(values[1].indices(), 39..46);
+    /// ```
+    pub fn list_values(&self) -> impl Iterator<Item = Span<str>> + '_ {
+        split_list(self.value.as_bytes(), b',')
+            .into_iter()
+            .filter_map(|range| self.value.0.subspan(range).try_into_str().ok())
+    }
+
+    /// Returns the authentication scheme and credentials of an `Authorization` or
+    /// `Proxy-Authorization` header.
+    ///
+    /// The credentials follow the scheme and one or more spaces, and are not decoded. If
+    /// there are no credentials the credentials span is empty. Returns `None` for any other
+    /// header, or if the value is not a valid UTF-8 string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(b"GET / HTTP/1.1\r\nAuthorization: Basic dXNlcjpwYXNz\r\n\r\n")
+    ///     .unwrap();
+    /// let header = req.headers_with_name("authorization").next().unwrap();
+    ///
+    /// let (scheme, credentials) = header.authorization().unwrap();
+    ///
+    /// assert_eq!(scheme, "Basic");
+    /// assert_eq!(credentials, "dXNlcjpwYXNz");
+    /// assert_eq!(credentials.indices(), 37..49);
+    /// ```
+    pub fn authorization(&self) -> Option<(Span<str>, Span<str>)> {
+        let name = self.name.as_str();
+        if !name.eq_ignore_ascii_case("authorization")
+            && !name.eq_ignore_ascii_case("proxy-authorization")
+        {
+            return None;
+        }
+
+        let value = self.value.as_bytes();
+        let value = self.value.0.subspan(trim_ows(value)).try_into_str().ok()?;
+
+        let (scheme_end, credentials_start) = match value.as_str().find(' ') {
+            Some(idx) => {
+                let spaces = value.as_str()[idx..]
+                    .find(|c| c != ' ')
+                    .unwrap_or(value.len() - idx);
+                (idx, idx + spaces)
+            }
+            None => (value.len(), value.len()),
+        };
+
+        Some((
+            value.subspan(0..scheme_end),
+            value.subspan(credentials_start..value.len()),
+        ))
+    }
+
+    /// Returns the components of an HTTP-date header value, such as the value of a
+    /// `Date`, `Last-Modified` or `Expires` header.
+    ///
+    /// The preferred IMF-fixdate format is supported, as well as the obsolete RFC 850 and
+    /// asctime formats, see RFC 9110, section 5.6.7. Returns `None` if the value is not in
+    /// one of these formats.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_response;
+    ///
+    /// let res = parse_response(
+    ///     b"HTTP/1.1 200 OK\r\nDate: Sun, 06 Nov 1994 08:49:37 GMT\r\nContent-Length: 0\r\n\r\n",
+    /// )
+    /// .unwrap();
+    /// let date = res.headers_with_name("date").next().unwrap().http_date().unwrap();
+    ///
+    /// assert_eq!(date.month, "Nov");
+    /// assert_eq!(date.year.indices(), 35..39);
+    /// ```
+    pub fn http_date(&self) -> Option<HttpDate> {
+        let value = self.value.as_bytes();
+        let trimmed = trim_ows(value);
+        let value = self.value.0.subspan(trimmed).try_into_str().ok()?;
+
+        let [weekday, day, month, year, time] = http_date_ranges(value.as_str())?;
+
+        Some(HttpDate {
+            weekday: value.subspan(weekday),
+            day: value.subspan(day),
+            month: value.subspan(month),
+            year: value.subspan(year),
+            time: value.subspan(time),
+        })
+    }
+
     /// Shifts the span range by the given offset.
     pub fn offset(&mut self, offset: usize) {
         self.span.offset(offset);
         self.name.offset(offset);
         self.value.offset(offset);
     }
+
+    /// Returns a copy of the header with its spans sliced from `src`, which starts at `origin`.
+    pub(crate) fn compact(&self, src: &Bytes, origin: usize) -> Self {
+        Header {
+            span: self.span.compact(src, origin),
+            name: HeaderName(self.name.0.compact(src, origin)),
+            value: HeaderValue(self.value.0.compact(src, origin)),
+        }
+    }
 }
 
 impl Spanned for Header {
@@ -81,6 +289,25 @@ impl Spanned for Header {
     }
 }
 
+/// The components of an HTTP-date, see [`Header::http_date`].
+///
+/// The components are not validated beyond their syntax, eg. the weekday may not match
+/// the date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HttpDate {
+    /// The day of the week, abbreviated except in the RFC 850 format.
+    pub weekday: Span<str>,
+    /// The day of the month, excluding the leading space of the asctime format.
+    pub day: Span<str>,
+    /// The abbreviated month.
+    pub month: Span<str>,
+    /// The year, which is two digits in the RFC 850 format.
+    pub year: Span<str>,
+    /// The time of day in the format `HH:MM:SS`.
+    pub time: Span<str>,
+}
+
 /// An HTTP request method.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -92,6 +319,11 @@ impl Method {
         self.0.as_str()
     }
 
+    /// Returns the kind of the method.
+    pub fn kind(&self) -> MethodKind {
+        MethodKind::from_token(self.as_str())
+    }
+
     /// Shifts the span range by the given offset.
     pub fn offset(&mut self, offset: usize) {
         self.0.offset(offset);
@@ -104,6 +336,52 @@ impl Spanned<str> for Method {
     }
 }
 
+/// The kind of an HTTP request method.
+///
+/// Method names are case-sensitive, so eg. `get` is [`MethodKind::Other`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MethodKind {
+    /// The `GET` method.
+    Get,
+    /// The `HEAD` method.
+    Head,
+    /// The `POST` method.
+    Post,
+    /// The `PUT` method.
+    Put,
+    /// The `DELETE` method.
+    Delete,
+    /// The `CONNECT` method.
+    Connect,
+    /// The `OPTIONS` method.
+    Options,
+    /// The `TRACE` method.
+    Trace,
+    /// The `PATCH` method.
+    Patch,
+    /// Any other method.
+    Other,
+}
+
+impl MethodKind {
+    /// Classifies a method token.
+    pub(crate) fn from_token(method: &str) -> Self {
+        match method {
+            "GET" => MethodKind::Get,
+            "HEAD" => MethodKind::Head,
+            "POST" => MethodKind::Post,
+            "PUT" => MethodKind::Put,
+            "DELETE" => MethodKind::Delete,
+            "CONNECT" => MethodKind::Connect,
+            "OPTIONS" => MethodKind::Options,
+            "TRACE" => MethodKind::Trace,
+            "PATCH" => MethodKind::Patch,
+            _ => MethodKind::Other,
+        }
+    }
+}
+
 /// An HTTP request target.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -115,6 +393,129 @@ impl Target {
         self.0.as_str()
     }
 
+    /// Returns the scheme of an absolute-form target, eg. `http` in
+    /// `http://example.com/path`.
+    ///
+    /// Returns `None` for any other form of target.
+    pub fn scheme(&self) -> Option<Span<str>> {
+        self.components().scheme.map(|range| self.0.subspan(range))
+    }
+
+    /// Returns the authority of an absolute-form target, eg. `example.com:8080` in
+    /// `http://example.com:8080/path`.
+    ///
+    /// Returns `None` for any other form of target.
+    pub fn authority(&self) -> Option<Span<str>> {
+        self.components()
+            .authority
+            .map(|range| self.0.subspan(range))
+    }
+
+    /// Returns the path of the target, excluding the query.
+    ///
+    /// The path of an absolute-form target may be empty, eg. in `http://example.com`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(b"GET http://example.com:8080/path?x=1 HTTP/1.1\r\n\r\n").unwrap();
+    /// let target = &req.request.target;
+    ///
+    /// assert_eq!(target.scheme().unwrap(), "http");
+    /// assert_eq!(target.authority().unwrap(), "example.com:8080");
+    /// assert_eq!(target.path(), "/path");
+    /// assert_eq!(target.query().unwrap(), "x=1");
+    /// ```
+    pub fn path(&self) -> Span<str> {
+        self.0.subspan(self.components().path)
+    }
+
+    /// Returns the query of the target, excluding the leading `?`.
+    ///
+    /// Returns `None` if the target does not have a query.
+    pub fn query(&self) -> Option<Span<str>> {
+        self.components().query.map(|range| self.0.subspan(range))
+    }
+
+    /// Returns the fragment of the target, excluding the leading `#`.
+    ///
+    /// Fragments are not sent by conforming clients, but some do. Returns `None` if the
+    /// target does not have a fragment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(b"GET /p?q=1#frag HTTP/1.1\r\n\r\n").unwrap();
+    /// let target = &req.request.target;
+    ///
+    /// assert_eq!(target.query().unwrap(), "q=1");
+    /// assert_eq!(target.fragment().unwrap(), "frag");
+    /// assert_eq!(target.fragment().unwrap().indices(), 11..15);
+    /// ```
+    pub fn fragment(&self) -> Option<Span<str>> {
+        self.components()
+            .fragment
+            .map(|range| self.0.subspan(range))
+    }
+
+    /// Splits the target into the ranges of its components, relative to the start of
+    /// the target.
+    fn components(&self) -> TargetComponents {
+        let target = self.as_str();
+
+        // An absolute-form target starts with a scheme followed by "://", see RFC 3986,
+        // section 3.1.
+        let scheme_end = target.find("://").filter(|&end| {
+            let scheme = &target.as_bytes()[..end];
+            scheme.first().is_some_and(u8::is_ascii_alphabetic)
+                && scheme
+                    .iter()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
+        });
+
+        let (scheme, authority, path_start) = match scheme_end {
+            Some(scheme_end) => {
+                let authority_start = scheme_end + 3;
+                let authority_end = target[authority_start..]
+                    .find(['/', '?', '#'])
+                    .map_or(target.len(), |idx| authority_start + idx);
+
+                (
+                    Some(0..scheme_end),
+                    Some(authority_start..authority_end),
+                    authority_end,
+                )
+            }
+            None => (None, None, 0),
+        };
+
+        let path_end = target[path_start..]
+            .find(['?', '#'])
+            .map_or(target.len(), |idx| path_start + idx);
+
+        let query = target[path_end..].strip_prefix('?').map(|rest| {
+            let end = rest.find('#').unwrap_or(rest.len());
+            path_end + 1..path_end + 1 + end
+        });
+
+        let query_end = query.as_ref().map_or(path_end, |query| query.end);
+        let fragment = target[query_end..]
+            .strip_prefix('#')
+            .map(|_| query_end + 1..target.len());
+
+        TargetComponents {
+            scheme,
+            authority,
+            path: path_start..path_end,
+            query,
+            fragment,
+        }
+    }
+
     /// Shifts the span range by the given offset.
     pub fn offset(&mut self, offset: usize) {
         self.0.offset(offset);
@@ -127,6 +528,221 @@ impl Spanned<str> for Target {
     }
 }
 
+/// Returns the host of an authority, eg. `example.com` in `example.com:8080`.
+///
+/// IPv6 literals keep their enclosing brackets, eg. `[::1]` in `[::1]:443`. Any user
+/// information preceding the host is excluded.
+///
+/// # Example
+///
+/// ```
+/// use spansy::http::{authority_host, authority_port, parse_request};
+///
+/// let req = parse_request(b"GET https://[::1]:443/ HTTP/1.1\r\n\r\n").unwrap();
+/// let authority = req.request.target.authority().unwrap();
+///
+/// assert_eq!(authority_host(&authority), "[::1]");
+/// assert_eq!(authority_port(&authority).unwrap(), "443");
+/// ```
+pub fn authority_host(span: &Span<str>) -> Span<str> {
+    span.subspan(authority_host_range(span.as_str()))
+}
+
+/// Returns the port of an authority, eg. `8080` in `example.com:8080`.
+///
+/// Returns `None` if the authority does not have a port, or if the port is empty.
+pub fn authority_port(span: &Span<str>) -> Option<Span<str>> {
+    let host_end = authority_host_range(span.as_str()).end;
+
+    span.as_str()[host_end..]
+        .strip_prefix(':')
+        .filter(|port| !port.is_empty())
+        .map(|_| span.subspan(host_end + 1..span.len()))
+}
+
+/// A base64 alphabet, see RFC 4648.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base64Alphabet {
+    /// The standard alphabet, using `+` and `/`.
+    Standard,
+    /// The URL and filename safe alphabet, using `-` and `_`.
+    UrlSafe,
+}
+
+/// Decodes a base64 encoded span, eg. the credentials of a `Basic` authorization.
+///
+/// Padding is optional, but if present it must be valid. Returns an error if the span
+/// contains characters outside of the alphabet or invalid padding.
+///
+/// # Example
+///
+/// ```
+/// use spansy::http::{base64_decode, parse_request, Base64Alphabet};
+///
+/// let req = parse_request(b"GET / HTTP/1.1\r\nAuthorization: Basic dXNlcjpwYXNz\r\n\r\n")
+///     .unwrap();
+/// let header = req.headers_with_name("authorization").next().unwrap();
+///
+/// let (_, credentials) = header.authorization().unwrap();
+///
+/// let decoded = base64_decode(&credentials.to_byte_span(), Base64Alphabet::Standard).unwrap();
+///
+/// assert_eq!(decoded.as_ref(), b"user:pass");
+/// ```
+pub fn base64_decode(span: &Span, alphabet: Base64Alphabet) -> Result<Bytes, ParseError> {
+    use base64::{
+        alphabet,
+        engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+        Engine,
+    };
+
+    let config =
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+    let engine = match alphabet {
+        Base64Alphabet::Standard => GeneralPurpose::new(&alphabet::STANDARD, config),
+        Base64Alphabet::UrlSafe => GeneralPurpose::new(&alphabet::URL_SAFE, config),
+    };
+
+    engine
+        .decode(span.as_bytes())
+        .map(Bytes::from)
+        .map_err(|err| ParseError(format!("invalid base64: {err}")))
+}
+
+/// Returns the span of the line terminators ending a message head, see
+/// [`Request::separator_span`].
+fn separator_span(head: Span, start_line_len: usize, headers: &[Header]) -> Span {
+    // The head of a message is a single range of the source, so an index is at
+    // `index - origin` within it.
+    let origin = head.indices.min().unwrap_or_default();
+    let Some(last) = headers.last() else {
+        return head.subspan(start_line_len..head.len());
+    };
+
+    // The header span includes its line terminator, which is CRLF or a bare LF.
+    let end = last.span.indices.max().map_or(0, |max| max + 1 - origin);
+    let start = if head.as_bytes()[..end].ends_with(b"\r\n") {
+        end - 2
+    } else {
+        end - 1
+    };
+
+    head.subspan(start..head.len())
+}
+
+/// Returns the ranges of the weekday, day, month, year and time of an HTTP-date.
+fn http_date_ranges(date: &str) -> Option<[Range<usize>; 5]> {
+    const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    const LONG_DAYS: [&str; 7] = [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    if !date.is_ascii() {
+        return None;
+    }
+
+    let b = date.as_bytes();
+    let digits = |range: Range<usize>| b[range].iter().all(u8::is_ascii_digit);
+    let month = |range: Range<usize>| MONTHS.contains(&&date[range]);
+    let time = |start: usize| {
+        digits(start..start + 2)
+            && b[start + 2] == b':'
+            && digits(start + 3..start + 5)
+            && b[start + 5] == b':'
+            && digits(start + 6..start + 8)
+    };
+
+    // IMF-fixdate, eg. `Sun, 06 Nov 1994 08:49:37 GMT`.
+    if b.len() == 29
+        && DAYS.contains(&&date[..3])
+        && &b[3..5] == b", "
+        && digits(5..7)
+        && b[7] == b' '
+        && month(8..11)
+        && b[11] == b' '
+        && digits(12..16)
+        && b[16] == b' '
+        && time(17)
+        && &b[25..] == b" GMT"
+    {
+        return Some([0..3, 5..7, 8..11, 12..16, 17..25]);
+    }
+
+    // RFC 850, eg. `Sunday, 06-Nov-94 08:49:37 GMT`.
+    if let Some(comma) = date.find(", ") {
+        let start = comma + 2;
+        if b.len() == start + 22
+            && LONG_DAYS.contains(&&date[..comma])
+            && digits(start..start + 2)
+            && b[start + 2] == b'-'
+            && month(start + 3..start + 6)
+            && b[start + 6] == b'-'
+            && digits(start + 7..start + 9)
+            && b[start + 9] == b' '
+            && time(start + 10)
+            && &b[start + 18..] == b" GMT"
+        {
+            return Some([
+                0..comma,
+                start..start + 2,
+                start + 3..start + 6,
+                start + 7..start + 9,
+                start + 10..start + 18,
+            ]);
+        }
+    }
+
+    // asctime, eg. `Sun Nov  6 08:49:37 1994`.
+    if b.len() == 24
+        && DAYS.contains(&&date[..3])
+        && b[3] == b' '
+        && month(4..7)
+        && b[7] == b' '
+        && (digits(8..10) || (b[8] == b' ' && digits(9..10)))
+        && b[10] == b' '
+        && time(11)
+        && b[19] == b' '
+        && digits(20..24)
+    {
+        let day = if b[8] == b' ' { 9..10 } else { 8..10 };
+        return Some([0..3, day, 4..7, 20..24, 11..19]);
+    }
+
+    None
+}
+
+/// Returns the range of the host within an authority.
+fn authority_host_range(authority: &str) -> Range<usize> {
+    let start = authority.rfind('@').map_or(0, |idx| idx + 1);
+    let host = &authority[start..];
+
+    let len = if host.starts_with('[') {
+        host.find(']').map_or(host.len(), |idx| idx + 1)
+    } else {
+        host.find(':').unwrap_or(host.len())
+    };
+
+    start..start + len
+}
+
+/// The ranges of the components of a request target, relative to the start of the target.
+struct TargetComponents {
+    scheme: Option<Range<usize>>,
+    authority: Option<Range<usize>>,
+    path: Range<usize>,
+    query: Option<Range<usize>>,
+    fragment: Option<Range<usize>>,
+}
+
 /// An HTTP request line, including the trailing CRLF.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -159,6 +775,49 @@ impl Spanned<str> for RequestLine {
     }
 }
 
+/// A conflict between the headers which determine the length of a message body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BodyLengthConflict {
+    /// Multiple `Content-Length` headers with differing values.
+    ContentLength(Vec<Header>),
+    /// Both `Content-Length` and `Transfer-Encoding` headers are present.
+    TransferEncoding {
+        /// The `Content-Length` headers.
+        content_length: Vec<Header>,
+        /// The `Transfer-Encoding` headers.
+        transfer_encoding: Vec<Header>,
+    },
+}
+
+/// A reference to a field of a parsed request, see [`Request::fields_within`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldRef<'a> {
+    /// The request method.
+    Method(&'a Method),
+    /// The request target.
+    Target(&'a Target),
+    /// The name of a header.
+    HeaderName(&'a HeaderName),
+    /// The value of a header.
+    HeaderValue(&'a HeaderValue),
+    /// The request body.
+    Body(&'a Body),
+}
+
+impl FieldRef<'_> {
+    /// Returns the indices of the field.
+    pub fn indices(&self) -> &RangeSet<usize> {
+        match self {
+            FieldRef::Method(method) => method.0.indices(),
+            FieldRef::Target(target) => target.0.indices(),
+            FieldRef::HeaderName(name) => name.0.indices(),
+            FieldRef::HeaderValue(value) => value.0.indices(),
+            FieldRef::Body(body) => body.span.indices(),
+        }
+    }
+}
+
 /// An HTTP request.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -170,6 +829,8 @@ pub struct Request {
     pub headers: Vec<Header>,
     /// Request body.
     pub body: Option<Body>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) source: Source,
 }
 
 impl Request {
@@ -183,127 +844,684 @@ impl Request {
             .filter(|h| h.name.0.as_str().eq_ignore_ascii_case(name))
     }
 
-    /// Returns the indices of the request excluding the target, headers and body.
-    pub fn without_data(&self) -> RangeSet<usize> {
-        let mut indices = self.span.indices.difference(&self.request.target.0.indices);
-        for header in &self.headers {
-            indices = indices.difference(header.span.indices());
-        }
-        if let Some(body) = &self.body {
-            indices = indices.difference(body.span.indices());
-        }
-        indices
+    /// Returns the first request header matching the given predicate.
+    pub fn find_header(&self, pred: impl Fn(&Header) -> bool) -> Option<&Header> {
+        self.headers.iter().find(|h| pred(h))
     }
 
-    /// Shifts the span range by the given offset.
-    pub fn offset(&mut self, offset: usize) {
-        self.span.offset(offset);
-        self.request.offset(offset);
-        for header in &mut self.headers {
-            header.offset(offset);
-        }
-        if let Some(body) = &mut self.body {
-            body.offset(offset);
-        }
+    /// Returns the indices of the request within the source.
+    ///
+    /// This is equivalent to `self.span().indices()`, and is the complement of
+    /// [`without_data`](Self::without_data) together with the data indices.
+    pub fn indices(&self) -> &RangeSet<usize> {
+        self.span.indices()
     }
-}
 
-impl Spanned for Request {
-    fn span(&self) -> &Span {
-        &self.span
+    /// Returns the source bytes the request was parsed from.
+    ///
+    /// This is a cheap clone of the buffer which all spans of the request are sliced from,
+    /// eg. the entire stream if the request was parsed by [`Requests`](crate::http::Requests).
+    /// The indices of the spans index directly into it, unless the request was
+    /// [offset](Self::offset). A [compacted](Self::compact) request only retains a copy of
+    /// its own bytes, and a deserialized request has an empty source.
+    pub fn source(&self) -> Bytes {
+        self.source.0.clone()
     }
-}
-
-/// An HTTP response code.
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Code(pub(crate) Span<str>);
 
-impl Code {
-    /// Returns the response code as a string slice.
-    pub fn as_str(&self) -> &str {
-        self.0.as_str()
+    /// Returns the span of the request head, ie. everything preceding the body.
+    ///
+    /// If the request has no body this is the span of the entire request.
+    pub fn head_span(&self) -> Span {
+        match &self.body {
+            Some(body) => self.span.subspan(0..self.span.len() - body.span.len()),
+            None => self.span.clone(),
+        }
     }
 
-    /// Shifts the span range by the given offset.
-    pub fn offset(&mut self, offset: usize) {
-        self.0.offset(offset);
+    /// Returns the span of the headers, including the empty line terminating them.
+    ///
+    /// This is the request head excluding the request line.
+    pub fn headers_span(&self) -> Span {
+        let head = self.head_span();
+
+        head.subspan(self.request.span.len()..head.len())
     }
-}
 
-impl Spanned<str> for Code {
-    fn span(&self) -> &Span<str> {
-        &self.0
+    /// Returns the span of the line terminators ending the head of the request.
+    ///
+    /// This is the empty line terminating the headers, preceded by the line terminator
+    /// of the last header if there are any headers, ie. `\r\n\r\n`. Without headers it is
+    /// only the empty line, as the line terminator of the request line is part of
+    /// [`RequestLine`].
+    pub fn separator_span(&self) -> Span {
+        separator_span(self.head_span(), self.request.span.len(), &self.headers)
     }
-}
 
-/// An HTTP response reason phrase.
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Reason(pub(crate) Span<str>);
+    /// Returns the value of the first `Host` header, excluding surrounding whitespace.
+    ///
+    /// Returns `None` if the request does not have a `Host` header, or if its value is
+    /// not a valid UTF-8 string.
+    pub fn host(&self) -> Option<Span<str>> {
+        let value = &self.headers_with_name("host").next()?.value.0;
 
-impl Reason {
-    /// Returns the response reason phrase as a string slice.
-    pub fn as_str(&self) -> &str {
-        self.0.as_str()
+        value
+            .subspan(trim_ows(value.as_bytes()))
+            .try_into_str()
+            .ok()
     }
 
-    /// Shifts the span range by the given offset.
-    pub fn offset(&mut self, offset: usize) {
-        self.0.offset(offset);
-    }
-}
+    /// Validates that the `Host` header is consistent with the request target.
+    ///
+    /// Returns an error if the request has more than one `Host` header, or if the target
+    /// is in absolute-form and its authority differs from the `Host` header. Hosts are
+    /// compared case-insensitively, while ports must be identical. An omitted port is the
+    /// default port of the scheme, ie. 80 for `http` and 443 for `https`. If only one of
+    /// the two is present the request is valid.
+    ///
+    /// A disagreement between the two is a common vector for request smuggling, as
+    /// intermediaries may route the request differently to the origin server.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(b"GET http://example.com/ HTTP/1.1\r\nHost: EXAMPLE.com\r\n\r\n").unwrap();
+    /// assert!(req.validate_host().is_ok());
+    ///
+    /// let req = parse_request(b"GET http://example.com/ HTTP/1.1\r\nHost: internal\r\n\r\n").unwrap();
+    /// assert!(req.validate_host().is_err());
+    /// ```
+    pub fn validate_host(&self) -> Result<(), ParseError> {
+        if self.headers_with_name("host").count() > 1 {
+            return Err(ParseError(
+                "request contains multiple Host headers".to_string(),
+            ));
+        }
 
-impl Spanned<str> for Reason {
-    fn span(&self) -> &Span<str> {
-        &self.0
-    }
-}
+        let (Some(authority), Some(host)) = (self.request.target.authority(), self.host()) else {
+            return Ok(());
+        };
 
-/// An HTTP response status.
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Status {
-    pub(crate) span: Span<str>,
+        let authority_without_userinfo = authority
+            .subspan(authority.as_str().rfind('@').map_or(0, |idx| idx + 1)..authority.len());
 
-    /// The response code.
-    pub code: Code,
-    /// The reason phrase.
-    pub reason: Reason,
-}
+        let default_port = self.request.target.scheme().and_then(|scheme| {
+            match scheme.as_str().to_ascii_lowercase().as_str() {
+                "http" => Some("80"),
+                "https" => Some("443"),
+                _ => None,
+            }
+        });
+        let port = |span: &Span<str>| {
+            authority_port(span)
+                .map(|port| port.as_str().to_string())
+                .or_else(|| default_port.map(str::to_string))
+        };
 
-impl Status {
-    /// Shifts the span range by the given offset.
-    pub fn offset(&mut self, offset: usize) {
-        self.span.offset(offset);
-        self.code.offset(offset);
-        self.reason.offset(offset);
-    }
-}
+        if !authority_host(&authority)
+            .as_str()
+            .eq_ignore_ascii_case(authority_host(&host).as_str())
+            || port(&authority) != port(&host)
+        {
+            return Err(ParseError(format!(
+                "Host header \"{}\" does not match request target authority \"{}\"",
+                host.as_str(),
+                authority_without_userinfo.as_str()
+            )));
+        }
 
-impl Spanned<str> for Status {
-    fn span(&self) -> &Span<str> {
-        &self.span
+        Ok(())
     }
-}
 
-/// An HTTP response.
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Response {
-    pub(crate) span: Span,
-    /// The response status.
-    pub status: Status,
-    /// Response headers.
-    pub headers: Vec<Header>,
-    /// Response body.
-    pub body: Option<Body>,
-}
-
-impl Response {
-    /// Returns an iterator of response headers with the given name (case-insensitive).
+    /// Returns the conflict between the headers which determine the length of the request
+    /// body, if any.
     ///
-    /// This method returns an iterator because it is valid for HTTP records to contain
+    /// A conflict is reported if both `Content-Length` and `Transfer-Encoding` headers are
+    /// present, or if multiple `Content-Length` headers have differing values. Such
+    /// requests may be framed differently by different servers, which is the basis of
+    /// request smuggling.
+    ///
+    /// `Content-Length` values are compared numerically, so `007` and `7` do not conflict,
+    /// while a value which is not a decimal number conflicts with any other value.
+    ///
+    /// This is advisory only. [`parse_request`](crate::http::parse_request) rejects
+    /// requests with either conflict, so use
+    /// [`parse_request_head`](crate::http::parse_request_head) to parse requests which may
+    /// have one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::{parse_request_head, BodyLengthConflict};
+    ///
+    /// let req = parse_request_head(b"POST / HTTP/1.1\r\nContent-Length: 1\r\n\r\n").unwrap();
+    /// assert!(req.body_length_ambiguity().is_none());
+    ///
+    /// let req = parse_request_head(
+    ///     b"POST / HTTP/1.1\r\nContent-Length: 1\r\nContent-Length: 2\r\n\r\nab",
+    /// )
+    /// .unwrap();
+    ///
+    /// let Some(BodyLengthConflict::ContentLength(headers)) = req.body_length_ambiguity() else {
+    ///     panic!("expected a conflict");
+    /// };
+    /// assert_eq!(headers.len(), 2);
+    /// ```
+    pub fn body_length_ambiguity(&self) -> Option<BodyLengthConflict> {
+        let content_length = self
+            .headers_with_name("content-length")
+            .cloned()
+            .collect::<Vec<_>>();
+        let transfer_encoding = self
+            .headers_with_name("transfer-encoding")
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !content_length.is_empty() && !transfer_encoding.is_empty() {
+            return Some(BodyLengthConflict::TransferEncoding {
+                content_length,
+                transfer_encoding,
+            });
+        }
+
+        // Values which are not a decimal number are compared as is.
+        fn length(header: &Header) -> Result<usize, &[u8]> {
+            let value = header.value.as_bytes();
+            let value = &value[trim_ows(value)];
+
+            if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+                return Err(value);
+            }
+
+            std::str::from_utf8(value)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .ok_or(value)
+        }
+
+        let first = content_length.first().map(length)?;
+        if content_length.iter().any(|header| length(header) != first) {
+            return Some(BodyLengthConflict::ContentLength(content_length));
+        }
+
+        None
+    }
+
+    /// Returns the byte ranges requested by the first `Range` header.
+    ///
+    /// Each range is a `(start, end)` pair with an inclusive end. An open-ended range,
+    /// eg. `500-`, has no end, and a suffix range, eg. `-500`, has no start.
+    ///
+    /// Returns `None` if the request does not have a `Range` header, if the header uses
+    /// a unit other than `bytes`, or if it is malformed. The header itself remains
+    /// accessible via [`Request::headers_with_name`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(b"GET / HTTP/1.1\r\nRange: bytes=0-499, 500-, -100\r\n\r\n").unwrap();
+    ///
+    /// assert_eq!(
+    ///     req.byte_ranges().unwrap(),
+    ///     vec![(Some(0), Some(499)), (Some(500), None), (None, Some(100))]
+    /// );
+    /// ```
+    pub fn byte_ranges(&self) -> Option<Vec<(Option<u64>, Option<u64>)>> {
+        let value = self.headers_with_name("range").next()?.value.as_bytes();
+        let value = &value[trim_ows(value)];
+
+        let (unit, ranges) = value.split_at(value.iter().position(|b| *b == b'=')?);
+        if !unit.eq_ignore_ascii_case(b"bytes") {
+            return None;
+        }
+        let ranges = &ranges[1..];
+
+        fn parse_pos(src: &[u8]) -> Option<Option<u64>> {
+            if src.is_empty() {
+                return Some(None);
+            }
+            if !src.iter().all(u8::is_ascii_digit) {
+                return None;
+            }
+            std::str::from_utf8(src).ok()?.parse().ok().map(Some)
+        }
+
+        let ranges = split_list(ranges, b',')
+            .into_iter()
+            .map(|range| {
+                let range = &ranges[range];
+                let dash = range.iter().position(|b| *b == b'-')?;
+                let start = parse_pos(&range[..dash])?;
+                let end = parse_pos(&range[dash + 1..])?;
+
+                match (start, end) {
+                    (None, None) => None,
+                    (Some(start), Some(end)) if start > end => None,
+                    range => Some(range),
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        (!ranges.is_empty()).then_some(ranges)
+    }
+
+    /// Returns the media ranges of the `Accept` headers, in order, paired with their
+    /// quality values.
+    ///
+    /// The span of each media range excludes its parameters. The quality value is `None`
+    /// if the media range does not have a `q` parameter, or if it is not a valid
+    /// quality value between 0 and 1. Media ranges which are not valid UTF-8 are
+    /// skipped. See [`Request::accept_sorted`] to order them by preference.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(
+    ///     b"GET / HTTP/1.1\r\nAccept: text/html,application/xml;q=0.9,*/*;q=0.8\r\n\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let accept = req.accept();
+    ///
+    /// assert_eq!(accept[0].0, "text/html");
+    /// assert_eq!(accept[1].0, "application/xml");
+    /// assert_eq!(
+    ///     accept.iter().map(|(_, q)| *q).collect::<Vec<_>>(),
+    ///     vec![None, Some(0.9), Some(0.8)]
+    /// );
+    /// ```
+    pub fn accept(&self) -> Vec<(Span<str>, Option<f32>)> {
+        fn quality(param: &[u8]) -> Option<f32> {
+            let eq = param.iter().position(|b| *b == b'=')?;
+            let (name, value) = (&param[..eq], &param[eq + 1..]);
+            if !name[trim_ows(name)].eq_ignore_ascii_case(b"q") {
+                return None;
+            }
+
+            // qvalue = ( "0" [ "." 0*3DIGIT ] ) / ( "1" [ "." 0*3("0") ] )
+            let value = &value[trim_ows(value)];
+            let (int, frac) = match value.iter().position(|b| *b == b'.') {
+                Some(dot) => (&value[..dot], &value[dot + 1..]),
+                None => (value, &b""[..]),
+            };
+            let valid = frac.len() <= 3
+                && match int {
+                    b"0" => frac.iter().all(u8::is_ascii_digit),
+                    b"1" => frac.iter().all(|b| *b == b'0'),
+                    _ => false,
+                };
+
+            valid
+                .then(|| std::str::from_utf8(value).ok()?.parse().ok())
+                .flatten()
+        }
+
+        self.headers_with_name("accept")
+            .flat_map(|header| {
+                let value = &header.value.0;
+                split_list(value.as_bytes(), b',')
+                    .into_iter()
+                    .filter_map(move |range| {
+                        let elem = &value.as_bytes()[range.clone()];
+                        let media_end = elem.iter().position(|b| *b == b';').unwrap_or(elem.len());
+                        let media = trim_ows(&elem[..media_end]);
+                        if media.is_empty() {
+                            return None;
+                        }
+
+                        let quality = elem[media_end..]
+                            .split(|b| *b == b';')
+                            .skip(1)
+                            .find_map(quality);
+                        let span = value
+                            .subspan(range.start + media.start..range.start + media.end)
+                            .try_into_str()
+                            .ok()?;
+
+                        Some((span, quality))
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the media ranges of the `Accept` headers ordered by preference, ie. by
+    /// descending quality value.
+    ///
+    /// Media ranges without a valid quality value have the default quality of 1. Media
+    /// ranges with equal quality keep their order. See [`Request::accept`].
+    pub fn accept_sorted(&self) -> Vec<(Span<str>, Option<f32>)> {
+        let mut accept = self.accept();
+        accept.sort_by(|(_, a), (_, b)| b.unwrap_or(1.0).total_cmp(&a.unwrap_or(1.0)));
+        accept
+    }
+
+    /// Returns the client addresses of the `X-Forwarded-For` headers, in order.
+    ///
+    /// The addresses of multiple headers are concatenated. The addresses are not
+    /// validated, see [`Header::list_values`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(
+    ///     b"GET / HTTP/1.1\r\nX-Forwarded-For: 203.0.113.7, 10.0.0.1\r\nX-Forwarded-For: 10.0.0.2\r\n\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let chain = req.forwarded_for();
+    ///
+    /// assert_eq!(chain, ["203.0.113.7", "10.0.0.1", "10.0.0.2"]);
+    /// assert_eq!(chain[1].indices(), 46..54);
+    /// ```
+    pub fn forwarded_for(&self) -> Vec<Span<str>> {
+        self.headers_with_name("x-forwarded-for")
+            .flat_map(Header::list_values)
+            .collect()
+    }
+
+    /// Returns the parameters of each hop of the `Forwarded` headers, in order, see
+    /// RFC 7239.
+    ///
+    /// Each hop is a list of parameter names and values, eg. `for` and `192.0.2.60`. The
+    /// quotes of a quoted value are excluded from its span, but escaped characters are
+    /// not unescaped. Parameters without a value are skipped, as are hops without any
+    /// parameters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_request;
+    ///
+    /// let req = parse_request(
+    ///     b"GET / HTTP/1.1\r\nForwarded: for=1.2.3.4;proto=https, for=\"[2001:db8::1]\"\r\n\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let hops = req.forwarded();
+    ///
+    /// assert_eq!(hops.len(), 2);
+    /// assert_eq!(hops[0][1].0, "proto");
+    /// assert_eq!(hops[0][1].1, "https");
+    /// assert_eq!(hops[1][0].1, "[2001:db8::1]");
+    /// ```
+    pub fn forwarded(&self) -> Vec<Vec<(Span<str>, Span<str>)>> {
+        self.headers_with_name("forwarded")
+            .flat_map(Header::list_values)
+            .filter_map(|hop| {
+                let params = split_list(hop.as_str().as_bytes(), b';')
+                    .into_iter()
+                    .filter_map(|range| {
+                        let param = hop.subspan(range);
+                        let src = param.as_str().as_bytes();
+                        let eq = src.iter().position(|b| *b == b'=')?;
+
+                        let name = trim_ows(&src[..eq]);
+                        let trimmed = trim_ows(&src[eq + 1..]);
+                        let mut value = eq + 1 + trimmed.start..eq + 1 + trimmed.end;
+
+                        let quoted = &src[value.clone()];
+                        if quoted.len() >= 2 && quoted.starts_with(b"\"") && quoted.ends_with(b"\"")
+                        {
+                            value = value.start + 1..value.end - 1;
+                        }
+
+                        Some((param.subspan(name), param.subspan(value)))
+                    })
+                    .collect::<Vec<_>>();
+
+                (!params.is_empty()).then_some(params)
+            })
+            .collect()
+    }
+
+    /// Returns the fields of the request which are fully covered by the given indices.
+    ///
+    /// The fields are returned in the order they appear in the request, with each header
+    /// name followed by its value. Empty fields, such as an empty header value, are always
+    /// covered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::{parse_request, FieldRef};
+    /// use utils::range::RangeSet;
+    ///
+    /// let req = parse_request(b"GET / HTTP/1.1\r\nHost: a\r\nCookie: secret\r\n\r\n").unwrap();
+    ///
+    /// // Everything except the cookie value is authorized.
+    /// let authorized = RangeSet::from(vec![0..33, 39..43]);
+    ///
+    /// let fields = req.fields_within(&authorized);
+    ///
+    /// assert_eq!(fields.len(), 5);
+    /// assert!(matches!(fields[4], FieldRef::HeaderName(name) if name.as_str() == "Cookie"));
+    /// ```
+    pub fn fields_within(&self, authorized: &RangeSet<usize>) -> Vec<FieldRef<'_>> {
+        let mut fields = vec![
+            FieldRef::Method(&self.request.method),
+            FieldRef::Target(&self.request.target),
+        ];
+        for header in &self.headers {
+            fields.push(FieldRef::HeaderName(&header.name));
+            fields.push(FieldRef::HeaderValue(&header.value));
+        }
+        if let Some(body) = &self.body {
+            fields.push(FieldRef::Body(body));
+        }
+
+        fields.retain(|field| field.indices().difference(authorized).is_empty());
+        fields
+    }
+
+    /// Returns the indices of the request excluding the target, headers and body.
+    pub fn without_data(&self) -> RangeSet<usize> {
+        let mut indices = self.span.indices.difference(&self.request.target.0.indices);
+        for header in &self.headers {
+            indices = indices.difference(header.span.indices());
+        }
+        if let Some(body) = &self.body {
+            indices = indices.difference(body.span.indices());
+        }
+        indices
+    }
+
+    /// Shifts the span range by the given offset.
+    ///
+    /// Every contained span is exposed with its own absolute indices, eg. through
+    /// [`Spanned::span`], so the spans can not share a single index base which is shifted
+    /// once. This shifts each of them instead, which is linear in the number of headers.
+    /// A zero offset is a no-op.
+    pub fn offset(&mut self, offset: usize) {
+        if offset == 0 {
+            return;
+        }
+
+        self.span.offset(offset);
+        self.request.offset(offset);
+        for header in &mut self.headers {
+            header.offset(offset);
+        }
+        if let Some(body) = &mut self.body {
+            body.offset(offset);
+        }
+    }
+
+    /// Returns a copy of the request which does not retain the original source.
+    ///
+    /// The returned request only holds a copy of its own bytes, so the source it was
+    /// parsed from can be freed. All indices are re-based to start at zero.
+    pub fn compact(self) -> Request {
+        // A parsed request is a single range of the source, so its bytes start at `origin`
+        // without gaps.
+        let origin = self.span.indices.min().unwrap_or_default();
+        let src = Bytes::copy_from_slice(self.span.as_bytes());
+
+        Request {
+            span: self.span.compact(&src, origin),
+            request: RequestLine {
+                span: self.request.span.compact(&src, origin),
+                method: Method(self.request.method.0.compact(&src, origin)),
+                target: Target(self.request.target.0.compact(&src, origin)),
+            },
+            headers: self
+                .headers
+                .iter()
+                .map(|header| header.compact(&src, origin))
+                .collect(),
+            body: self.body.map(|body| Body {
+                span: body.span.compact(&src, origin),
+            }),
+            source: Source(src),
+        }
+    }
+}
+
+impl Spanned for Request {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+/// An HTTP response code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Code(pub(crate) Span<str>);
+
+impl Code {
+    /// Returns the response code as a string slice.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Shifts the span range by the given offset.
+    pub fn offset(&mut self, offset: usize) {
+        self.0.offset(offset);
+    }
+}
+
+impl Spanned<str> for Code {
+    fn span(&self) -> &Span<str> {
+        &self.0
+    }
+}
+
+/// An HTTP response reason phrase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reason(pub(crate) Span<str>);
+
+impl Reason {
+    /// Returns the response reason phrase as a string slice.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Shifts the span range by the given offset.
+    pub fn offset(&mut self, offset: usize) {
+        self.0.offset(offset);
+    }
+}
+
+impl Spanned<str> for Reason {
+    fn span(&self) -> &Span<str> {
+        &self.0
+    }
+}
+
+/// An HTTP response status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Status {
+    pub(crate) span: Span<str>,
+
+    /// The response code.
+    pub code: Code,
+    /// The reason phrase.
+    pub reason: Reason,
+}
+
+impl Status {
+    /// Returns `true` if the status line has a non-empty reason phrase.
+    ///
+    /// The reason phrase is optional, in which case its span is empty.
+    pub fn has_reason(&self) -> bool {
+        !self.reason.0.is_empty()
+    }
+
+    /// Shifts the span range by the given offset.
+    pub fn offset(&mut self, offset: usize) {
+        self.span.offset(offset);
+        self.code.offset(offset);
+        self.reason.offset(offset);
+    }
+}
+
+impl Spanned<str> for Status {
+    fn span(&self) -> &Span<str> {
+        &self.span
+    }
+}
+
+/// A byte range of a representation, as specified by a `Content-Range` header.
+///
+/// The end of the range is inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContentRange {
+    /// The first byte position.
+    pub start: u64,
+    /// The last byte position.
+    pub end: u64,
+    /// The complete length of the representation, `None` if unknown.
+    pub total: Option<u64>,
+    /// The span of the first byte position.
+    pub start_span: Span<str>,
+    /// The span of the last byte position.
+    pub end_span: Span<str>,
+    /// The span of the complete length, `None` if unknown.
+    pub total_span: Option<Span<str>>,
+}
+
+/// How the length of a response body is determined, see RFC 9112, section 6.3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BodyFraming {
+    /// The body length is given by the `Content-Length` header.
+    ContentLength(usize),
+    /// The body is encoded with the chunked transfer coding.
+    Chunked,
+    /// The body is delimited by the server closing the connection.
+    UntilClose,
+    /// The response can not contain a body.
+    None,
+}
+
+/// An HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Response {
+    pub(crate) span: Span,
+    /// The response status.
+    pub status: Status,
+    /// Response headers.
+    pub headers: Vec<Header>,
+    /// Response body.
+    pub body: Option<Body>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) source: Source,
+}
+
+impl Response {
+    /// Returns an iterator of response headers with the given name (case-insensitive).
+    ///
+    /// This method returns an iterator because it is valid for HTTP records to contain
     /// duplicate header names.
     pub fn headers_with_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Header> {
         self.headers
@@ -311,6 +1529,224 @@ impl Response {
             .filter(|h| h.name.0.as_str().eq_ignore_ascii_case(name))
     }
 
+    /// Returns the first response header matching the given predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_response;
+    ///
+    /// let res = parse_response(
+    ///     b"HTTP/1.1 200 OK\r\nVary: Accept\r\nContent-Encoding: gzip\r\nContent-Length: 0\r\n\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let header = res.find_header(|h| h.value.as_bytes() == b"gzip").unwrap();
+    /// assert_eq!(header.name.as_str(), "Content-Encoding");
+    /// ```
+    pub fn find_header(&self, pred: impl Fn(&Header) -> bool) -> Option<&Header> {
+        self.headers.iter().find(|h| pred(h))
+    }
+
+    /// Returns the indices of the response within the source.
+    ///
+    /// This is equivalent to `self.span().indices()`, and is the complement of
+    /// [`without_data`](Self::without_data) together with the data indices.
+    pub fn indices(&self) -> &RangeSet<usize> {
+        self.span.indices()
+    }
+
+    /// Returns the source bytes the response was parsed from.
+    ///
+    /// This is a cheap clone of the buffer which all spans of the response are sliced from,
+    /// eg. the entire stream if the response was parsed by [`Responses`](crate::http::Responses).
+    /// The indices of the spans index directly into it, unless the response was
+    /// [offset](Self::offset). A [compacted](Self::compact) response only retains a copy of
+    /// its own bytes, and a deserialized response has an empty source.
+    pub fn source(&self) -> Bytes {
+        self.source.0.clone()
+    }
+
+    /// Returns the span of the response head, ie. everything preceding the body.
+    ///
+    /// If the response has no body this is the span of the entire response.
+    pub fn head_span(&self) -> Span {
+        match &self.body {
+            Some(body) => self.span.subspan(0..self.span.len() - body.span.len()),
+            None => self.span.clone(),
+        }
+    }
+
+    /// Returns the span of the headers, including the empty line terminating them.
+    ///
+    /// This is the response head excluding the status line.
+    pub fn headers_span(&self) -> Span {
+        let head = self.head_span();
+
+        head.subspan(self.status.span.len()..head.len())
+    }
+
+    /// Returns the span of the line terminators ending the head of the response.
+    ///
+    /// This is the empty line terminating the headers, preceded by the line terminator
+    /// of the last header if there are any headers, ie. `\r\n\r\n`. Without headers it is
+    /// only the empty line, as the line terminator of the status line is part of
+    /// [`Status`].
+    pub fn separator_span(&self) -> Span {
+        separator_span(self.head_span(), self.status.span.len(), &self.headers)
+    }
+
+    /// Returns `true` if the response is an interim response with a 1xx (Informational)
+    /// status code, such as `100 Continue`, which precedes the final response to a
+    /// request.
+    pub fn is_informational(&self) -> bool {
+        self.status.code.as_str().starts_with('1')
+    }
+
+    /// Returns how the length of the response body is determined.
+    ///
+    /// Responses with a 1xx (Informational), 204 (No Content) or 304 (Not Modified) status
+    /// code never have a body. Otherwise `Transfer-Encoding` takes precedence over
+    /// `Content-Length`: if chunked is the final transfer coding the body is chunked,
+    /// and with any other coding it is delimited by the connection closing. Without
+    /// either header, or if the `Content-Length` values are invalid, the body is also
+    /// delimited by the connection closing.
+    ///
+    /// Responses to HEAD requests never have a body either, but the request method is
+    /// not known here.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::{parse_response, BodyFraming};
+    ///
+    /// let res = parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello").unwrap();
+    ///
+    /// assert_eq!(res.body_framing(), BodyFraming::ContentLength(5));
+    /// ```
+    pub fn body_framing(&self) -> BodyFraming {
+        if matches!(
+            self.status.code.as_str().parse::<u16>(),
+            Ok(100..=199 | 204 | 304)
+        ) {
+            return BodyFraming::None;
+        }
+
+        let mut codings = self
+            .headers_with_name("transfer-encoding")
+            .flat_map(|header| {
+                let value = header.value.as_bytes();
+                split_list(value, b',')
+                    .into_iter()
+                    .map(move |range| &value[range])
+            })
+            .peekable();
+
+        if codings.peek().is_some() {
+            return if codings
+                .last()
+                .is_some_and(|coding| coding.eq_ignore_ascii_case(b"chunked"))
+            {
+                BodyFraming::Chunked
+            } else {
+                BodyFraming::UntilClose
+            };
+        }
+
+        match content_length(self.headers_with_name("content-length")) {
+            Ok(Some(len)) => BodyFraming::ContentLength(len),
+            _ => BodyFraming::UntilClose,
+        }
+    }
+
+    /// Returns the value of the first `Content-Encoding` header, excluding surrounding
+    /// whitespace.
+    ///
+    /// Returns `None` if the response does not have a `Content-Encoding` header, if its
+    /// value is `identity` or empty, or if it is not a valid UTF-8 string. The body can be
+    /// decoded with [`Body::decode_content`] if the `compression` feature is enabled.
+    pub fn is_body_encoded(&self) -> Option<Span<str>> {
+        let value = &self.headers_with_name("content-encoding").next()?.value.0;
+        let encoding = value
+            .subspan(trim_ows(value.as_bytes()))
+            .try_into_str()
+            .ok()?;
+
+        if encoding.is_empty() || encoding.as_str().eq_ignore_ascii_case("identity") {
+            return None;
+        }
+
+        Some(encoding)
+    }
+
+    /// Returns the byte range specified by the first `Content-Range` header.
+    ///
+    /// Returns `None` if the response does not have a `Content-Range` header, if the
+    /// header uses a unit other than `bytes`, if it specifies an unsatisfied range,
+    /// eg. `bytes */1234`, or if it is malformed. The header itself remains accessible
+    /// via [`Response::headers_with_name`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::http::parse_response;
+    ///
+    /// let res = parse_response(
+    ///     b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 0-499/1234\r\nContent-Length: 0\r\n\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let range = res.content_range().unwrap();
+    ///
+    /// assert_eq!((range.start, range.end, range.total), (0, 499, Some(1234)));
+    /// assert_eq!(range.end_span, "499");
+    /// ```
+    pub fn content_range(&self) -> Option<ContentRange> {
+        let value = &self.headers_with_name("content-range").next()?.value.0;
+        let src = value.as_bytes();
+        let trimmed = trim_ows(src);
+
+        let unit_end = trimmed.start + src[trimmed.clone()].iter().position(|b| *b == b' ')?;
+        if !src[trimmed.start..unit_end].eq_ignore_ascii_case(b"bytes") {
+            return None;
+        }
+
+        let range = unit_end + 1..trimmed.end;
+        let dash = range.start + src[range.clone()].iter().position(|b| *b == b'-')?;
+        let slash = dash + src[dash..range.end].iter().position(|b| *b == b'/')?;
+
+        let pos = |range: Range<usize>| -> Option<(u64, Span<str>)> {
+            let digits = &src[range.clone()];
+            if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+                return None;
+            }
+            let span = value.subspan(range).try_into_str().ok()?;
+            Some((span.as_str().parse().ok()?, span))
+        };
+
+        let (start, start_span) = pos(range.start..dash)?;
+        let (end, end_span) = pos(dash + 1..slash)?;
+        let (total, total_span) = if &src[slash + 1..range.end] == b"*" {
+            (None, None)
+        } else {
+            let (total, span) = pos(slash + 1..range.end)?;
+            (Some(total), Some(span))
+        };
+
+        if start > end || total.is_some_and(|total| end >= total) {
+            return None;
+        }
+
+        Some(ContentRange {
+            start,
+            end,
+            total,
+            start_span,
+            end_span,
+            total_span,
+        })
+    }
+
     /// Returns the indices of the response excluding the headers and body.
     pub fn without_data(&self) -> RangeSet<usize> {
         let mut indices = self.span.indices.clone();
@@ -324,7 +1760,16 @@ impl Response {
     }
 
     /// Shifts the span range by the given offset.
+    ///
+    /// Every contained span is exposed with its own absolute indices, eg. through
+    /// [`Spanned::span`], so the spans can not share a single index base which is shifted
+    /// once. This shifts each of them instead, which is linear in the number of headers.
+    /// A zero offset is a no-op.
     pub fn offset(&mut self, offset: usize) {
+        if offset == 0 {
+            return;
+        }
+
         self.span.offset(offset);
         self.status.offset(offset);
         for header in &mut self.headers {
@@ -334,6 +1779,35 @@ impl Response {
             body.offset(offset);
         }
     }
+
+    /// Returns a copy of the response which does not retain the original source.
+    ///
+    /// The returned response only holds a copy of its own bytes, so the source it was
+    /// parsed from can be freed. All indices are re-based to start at zero.
+    pub fn compact(self) -> Response {
+        // A parsed response is a single range of the source, so its bytes start at `origin`
+        // without gaps.
+        let origin = self.span.indices.min().unwrap_or_default();
+        let src = Bytes::copy_from_slice(self.span.as_bytes());
+
+        Response {
+            span: self.span.compact(&src, origin),
+            status: Status {
+                span: self.status.span.compact(&src, origin),
+                code: Code(self.status.code.0.compact(&src, origin)),
+                reason: Reason(self.status.reason.0.compact(&src, origin)),
+            },
+            headers: self
+                .headers
+                .iter()
+                .map(|header| header.compact(&src, origin))
+                .collect(),
+            body: self.body.map(|body| Body {
+                span: body.span.compact(&src, origin),
+            }),
+            source: Source(src),
+        }
+    }
 }
 
 impl Spanned for Response {
@@ -355,6 +1829,125 @@ impl Body {
         self.span.as_bytes()
     }
 
+    /// Parses the body as a JSON value.
+    ///
+    /// The spans of the returned value index into the source of the message, rather than
+    /// the body.
+    ///
+    /// Returns an error if the body is not contiguous in the source, as the value is
+    /// parsed from the body bytes and shifted to the start of the body.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::{http::parse_response, Spanned};
+    ///
+    /// let src = b"HTTP/1.1 200 OK\r\nContent-Length: 8\r\n\r\n{\"a\": 1}";
+    /// let resp = parse_response(src).unwrap();
+    ///
+    /// let value = resp.body.unwrap().as_json().unwrap();
+    /// assert_eq!(value.get("a").unwrap().span().indices(), 44..45);
+    /// ```
+    pub fn as_json(&self) -> Result<JsonValue, ParseError> {
+        if self.span.indices.len_ranges() > 1 {
+            return Err(ParseError(
+                "body is not contiguous in the source".to_string(),
+            ));
+        }
+
+        let mut value = json::parse(self.span.data.clone())?;
+        if let Some(start) = self.span.indices.min() {
+            value.offset(start);
+        }
+
+        Ok(value)
+    }
+
+    /// Returns the body decoded according to the given content codings, eg. the value of
+    /// the `Content-Encoding` header, see [`Response::is_body_encoded`].
+    ///
+    /// The codings are a comma separated list in the order they were applied, so they are
+    /// decoded in reverse. `gzip`, `x-gzip`, `deflate` and `identity` are supported, and
+    /// `br` if the `brotli` feature is enabled.
+    ///
+    /// Decoding destroys the relationship between the body and its source, so the
+    /// decoded content is returned as owned bytes rather than a span. Indices into the
+    /// decoded content do not correspond to indices of the source.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use flate2::{write::GzEncoder, Compression};
+    /// use spansy::http::parse_response;
+    ///
+    /// let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    /// encoder.write_all(b"hello world").unwrap();
+    /// let body = encoder.finish().unwrap();
+    ///
+    /// let mut src = format!(
+    ///     "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\n\r\n",
+    ///     body.len()
+    /// )
+    /// .into_bytes();
+    /// src.extend_from_slice(&body);
+    ///
+    /// let res = parse_response(&src).unwrap();
+    /// let encoding = res.is_body_encoded().unwrap();
+    ///
+    /// let content = res.body.unwrap().decode_content(encoding.as_str()).unwrap();
+    ///
+    /// assert_eq!(content.as_ref(), b"hello world");
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn decode_content(&self, encoding: &str) -> Result<Bytes, ParseError> {
+        use std::io::Read;
+
+        fn read_to_end(coding: &str, mut reader: impl Read) -> Result<Vec<u8>, ParseError> {
+            let mut out = Vec::new();
+            reader
+                .read_to_end(&mut out)
+                .map_err(|err| ParseError(format!("failed to decode {coding} content: {err}")))?;
+            Ok(out)
+        }
+
+        let mut content = self.span.as_bytes().to_vec();
+        for range in split_list(encoding.as_bytes(), b',').into_iter().rev() {
+            let coding = &encoding[range];
+            content = match coding.to_ascii_lowercase().as_str() {
+                "identity" => content,
+                "gzip" | "x-gzip" => read_to_end(
+                    coding,
+                    flate2::read::MultiGzDecoder::new(content.as_slice()),
+                )?,
+                // The deflate coding is the zlib format, but some servers send raw deflate
+                // data instead.
+                "deflate" => {
+                    read_to_end(coding, flate2::read::ZlibDecoder::new(content.as_slice()))
+                        .or_else(|_| {
+                            read_to_end(
+                                coding,
+                                flate2::read::DeflateDecoder::new(content.as_slice()),
+                            )
+                        })?
+                }
+                #[cfg(feature = "brotli")]
+                "br" => read_to_end(
+                    coding,
+                    brotli_decompressor::Decompressor::new(content.as_slice(), 4096),
+                )?,
+                _ => {
+                    return Err(ParseError(format!(
+                        "unsupported content encoding: {coding}"
+                    )))
+                }
+            };
+        }
+
+        Ok(Bytes::from(content))
+    }
+
     /// Shifts the span range by the given offset.
     pub fn offset(&mut self, offset: usize) {
         self.span.offset(offset);
@@ -366,3 +1959,886 @@ impl Spanned for Body {
         &self.span
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use utils::range::RangeUnion;
+
+    use crate::http::{parse_request, parse_request_head, parse_response};
+
+    use super::*;
+
+    /// Builds a header from a single `name: value\r\n` line, for headers which are
+    /// rejected by the parser.
+    fn header(src: &'static [u8]) -> Header {
+        let src = Bytes::from_static(src);
+        let colon = src.iter().position(|b| *b == b':').unwrap();
+
+        Header {
+            span: Span::new_bytes(&src, 0..src.len()),
+            name: HeaderName(Span::new_str(&src, 0..colon)),
+            value: HeaderValue(Span::new_bytes(&src, colon + 2..src.len() - 2)),
+        }
+    }
+
+    #[test]
+    fn test_header_value_eq_ignore_case() {
+        let req = parse_request(b"GET / HTTP/1.1\r\nConnection: Keep-Alive  \r\n\r\n").unwrap();
+        let header = req.headers_with_name("connection").next().unwrap();
+
+        assert!(header.value_eq_ignore_case("keep-alive"));
+        assert!(!header.value_eq_ignore_case("close"));
+    }
+
+    #[test]
+    fn test_header_list_values() {
+        let src = b"GET / HTTP/1.1\r\nConnection: keep-alive, Upgrade \r\n\
+            Foo: a, \"b, c\", , d\r\n\r\n";
+        let req = parse_request(src).unwrap();
+
+        let values = req
+            .headers_with_name("connection")
+            .next()
+            .unwrap()
+            .list_values()
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["keep-alive", "Upgrade"]);
+        assert_eq!(values[1].indices(), 40..47);
+
+        let values = req
+            .headers_with_name("foo")
+            .next()
+            .unwrap()
+            .list_values()
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["a", "\"b, c\"", "d"]);
+    }
+
+    #[test]
+    fn test_header_authorization() {
+        let req = parse_request(
+            b"GET / HTTP/1.1\r\nProxy-Authorization:  Bearer   abc.def \r\n\
+            Authorization: Negotiate\r\nHost: a\r\n\r\n",
+        )
+        .unwrap();
+
+        let (scheme, credentials) = req.headers[0].authorization().unwrap();
+        assert_eq!(scheme, "Bearer");
+        assert_eq!(scheme, 38..44);
+        assert_eq!(credentials, "abc.def");
+        assert_eq!(credentials, 47..54);
+
+        let (scheme, credentials) = req.headers[1].authorization().unwrap();
+        assert_eq!(scheme, "Negotiate");
+        assert!(credentials.is_empty());
+
+        assert!(req.headers[2].authorization().is_none());
+    }
+
+    #[test]
+    fn test_base64_decode() {
+        let src = Bytes::from_static(b"aGk/Pz8= aGk_Pz8 aGk/Pz8=== aGk*");
+        let span = |range| Span::new_bytes(&src, range);
+
+        assert_eq!(
+            base64_decode(&span(0..8), Base64Alphabet::Standard).unwrap(),
+            b"hi???".as_slice()
+        );
+        assert_eq!(
+            base64_decode(&span(9..16), Base64Alphabet::UrlSafe).unwrap(),
+            b"hi???".as_slice()
+        );
+        assert!(base64_decode(&span(9..16), Base64Alphabet::Standard).is_err());
+        assert!(base64_decode(&span(0..8), Base64Alphabet::UrlSafe).is_err());
+        assert!(base64_decode(&span(17..27), Base64Alphabet::Standard).is_err());
+        assert_eq!(
+            base64_decode(&span(28..32), Base64Alphabet::Standard)
+                .unwrap_err()
+                .to_string(),
+            "parsing error: invalid base64: Invalid symbol 42, offset 3."
+        );
+    }
+
+    #[test]
+    fn test_target_components() {
+        let req =
+            parse_request(b"GET http://example.com:8080/a/b?x=1&y#frag HTTP/1.1\r\n\r\n").unwrap();
+        let target = &req.request.target;
+
+        assert_eq!(target.scheme().unwrap(), 4..8);
+        assert_eq!(target.authority().unwrap(), "example.com:8080");
+        assert_eq!(target.authority().unwrap(), 11..27);
+        assert_eq!(target.path(), "/a/b");
+        assert_eq!(target.path(), 27..31);
+        assert_eq!(target.query().unwrap(), "x=1&y");
+        assert_eq!(target.query().unwrap(), 32..37);
+
+        let req = parse_request(b"GET http://example.com?x HTTP/1.1\r\n\r\n").unwrap();
+        let target = &req.request.target;
+
+        assert_eq!(target.authority().unwrap(), "example.com");
+        assert!(target.path().is_empty());
+        assert_eq!(target.query().unwrap(), "x");
+
+        let req = parse_request(b"GET /index.html HTTP/1.1\r\n\r\n").unwrap();
+        let target = &req.request.target;
+
+        assert!(target.scheme().is_none());
+        assert!(target.authority().is_none());
+        assert_eq!(target.path(), "/index.html");
+        assert!(target.query().is_none());
+
+        let req = parse_request(b"GET /redirect?to=http://a.b HTTP/1.1\r\n\r\n").unwrap();
+        let target = &req.request.target;
+
+        assert!(target.scheme().is_none());
+        assert_eq!(target.path(), "/redirect");
+        assert_eq!(target.query().unwrap(), "to=http://a.b");
+    }
+
+    #[test]
+    fn test_target_fragment() {
+        let req = parse_request(b"GET /p?q=1#frag HTTP/1.1\r\n\r\n").unwrap();
+        let target = &req.request.target;
+
+        assert_eq!(target.path(), "/p");
+        assert_eq!(target.query().unwrap(), "q=1");
+        assert_eq!(target.fragment().unwrap(), "frag");
+        assert_eq!(target.fragment().unwrap(), 11..15);
+
+        let req = parse_request(b"GET /p#a?b HTTP/1.1\r\n\r\n").unwrap();
+        let target = &req.request.target;
+
+        assert_eq!(target.path(), "/p");
+        assert!(target.query().is_none());
+        assert_eq!(target.fragment().unwrap(), "a?b");
+
+        let req = parse_request(b"GET http://a.b#x HTTP/1.1\r\n\r\n").unwrap();
+        let target = &req.request.target;
+
+        assert_eq!(target.authority().unwrap(), "a.b");
+        assert_eq!(target.fragment().unwrap(), "x");
+
+        let req = parse_request(b"GET /p?q# HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(req.request.target.query().unwrap(), "q");
+        assert!(req.request.target.fragment().unwrap().is_empty());
+
+        let req = parse_request(b"GET /p?q=1 HTTP/1.1\r\n\r\n").unwrap();
+        assert!(req.request.target.fragment().is_none());
+    }
+
+    #[test]
+    fn test_authority_host_port() {
+        let src = Bytes::from_static(b"example.com:8080 [::1]:443 [::1] user@host: localhost");
+        let authority = |range| Span::new_str(&src, range);
+
+        let example = authority(0..16);
+        assert_eq!(authority_host(&example), 0..11);
+        assert_eq!(authority_port(&example).unwrap(), 12..16);
+
+        let ipv6 = authority(17..26);
+        assert_eq!(authority_host(&ipv6), "[::1]");
+        assert_eq!(authority_port(&ipv6).unwrap(), "443");
+        assert_eq!(authority_port(&ipv6).unwrap(), 23..26);
+
+        let ipv6 = authority(27..32);
+        assert_eq!(authority_host(&ipv6), "[::1]");
+        assert!(authority_port(&ipv6).is_none());
+
+        let userinfo = authority(33..43);
+        assert_eq!(authority_host(&userinfo), "host");
+        assert!(authority_port(&userinfo).is_none());
+
+        let localhost = authority(44..53);
+        assert_eq!(authority_host(&localhost), "localhost");
+        assert!(authority_port(&localhost).is_none());
+    }
+
+    #[test]
+    fn test_request_host() {
+        let req = parse_request(b"GET / HTTP/1.1\r\nHost:  example.com:80 \r\n\r\n").unwrap();
+
+        assert_eq!(req.host().unwrap(), "example.com:80");
+        assert_eq!(req.host().unwrap(), 23..37);
+        assert!(req.validate_host().is_ok());
+
+        assert!(parse_request(b"GET / HTTP/1.1\r\n\r\n")
+            .unwrap()
+            .host()
+            .is_none());
+    }
+
+    #[test]
+    fn test_header_name_to_canonical() {
+        let cases = [
+            ("content-type", "Content-Type"),
+            ("Content-Type", "Content-Type"),
+            ("CONTENT-LENGTH", "Content-Length"),
+            ("host", "Host"),
+            ("x-forwarded-for", "X-Forwarded-For"),
+            ("etag", "ETag"),
+            ("ETAG", "ETag"),
+            ("www-authenticate", "WWW-Authenticate"),
+            ("te", "TE"),
+            ("dnt", "DNT"),
+            ("content-md5", "Content-MD5"),
+            ("x-xss-protection", "X-XSS-Protection"),
+            ("sec-websocket-key", "Sec-WebSocket-Key"),
+            ("x--double", "X--Double"),
+        ];
+
+        for (name, canonical) in cases {
+            let req =
+                parse_request(format!("GET / HTTP/1.1\r\n{name}: 0\r\n\r\n").as_bytes()).unwrap();
+            assert_eq!(req.headers[0].name.to_canonical(), canonical, "{name}");
+            assert_eq!(req.headers[0].name.as_str(), name);
+        }
+    }
+
+    #[test]
+    fn test_request_byte_ranges() {
+        let ranges = |value: &str| {
+            parse_request(format!("GET / HTTP/1.1\r\nRange: {value}\r\n\r\n").as_bytes())
+                .unwrap()
+                .byte_ranges()
+        };
+
+        assert_eq!(ranges("bytes=0-499").unwrap(), vec![(Some(0), Some(499))]);
+        assert_eq!(
+            ranges("Bytes=500-,-10").unwrap(),
+            vec![(Some(500), None), (None, Some(10))]
+        );
+        assert!(ranges("items=0-1").is_none());
+        assert!(ranges("bytes=").is_none());
+        assert!(ranges("bytes=-").is_none());
+        assert!(ranges("bytes=5-1").is_none());
+        assert!(ranges("bytes=0-1,a-2").is_none());
+        assert!(ranges("bytes=0-+1").is_none());
+        assert!(ranges("bytes 0-1").is_none());
+
+        assert!(parse_request(b"GET / HTTP/1.1\r\n\r\n")
+            .unwrap()
+            .byte_ranges()
+            .is_none());
+    }
+
+    #[test]
+    fn test_response_body_framing() {
+        let mut res = parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+        assert_eq!(res.body_framing(), BodyFraming::ContentLength(0));
+
+        // Transfer-Encoding takes precedence over Content-Length.
+        res.headers.push(header(b"Transfer-Encoding: gzip\r\n"));
+        assert_eq!(res.body_framing(), BodyFraming::UntilClose);
+        res.headers.push(header(b"Transfer-Encoding: Chunked\r\n"));
+        assert_eq!(res.body_framing(), BodyFraming::Chunked);
+
+        assert_eq!(
+            parse_response(b"HTTP/1.1 304 Not Modified\r\nContent-Length: 5\r\n\r\n")
+                .unwrap()
+                .body_framing(),
+            BodyFraming::None
+        );
+
+        res.headers.clear();
+        assert_eq!(res.body_framing(), BodyFraming::UntilClose);
+    }
+
+    #[test]
+    fn test_response_is_body_encoded() {
+        let res = parse_response(
+            b"HTTP/1.1 200 OK\r\nContent-Encoding:  gzip, br \r\nContent-Length: 0\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(res.is_body_encoded().unwrap(), "gzip, br");
+        assert_eq!(res.is_body_encoded().unwrap(), 36..44);
+
+        let res = parse_response(
+            b"HTTP/1.1 200 OK\r\nContent-Encoding: identity\r\nContent-Length: 0\r\n\r\n",
+        )
+        .unwrap();
+        assert!(res.is_body_encoded().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn test_body_decode_content() {
+        use std::io::Write;
+
+        use flate2::{
+            write::{DeflateEncoder, GzEncoder, ZlibEncoder},
+            Compression,
+        };
+
+        fn body(content: Vec<u8>) -> Body {
+            Body {
+                span: Span::new_bytes(&Bytes::from(content.clone()), 0..content.len()),
+            }
+        }
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(b"hello").unwrap();
+        let gzip = gzip.finish().unwrap();
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(&gzip).unwrap();
+        let zlib = zlib.finish().unwrap();
+
+        let mut deflate = DeflateEncoder::new(Vec::new(), Compression::default());
+        deflate.write_all(b"hello").unwrap();
+        let deflate = deflate.finish().unwrap();
+
+        assert_eq!(
+            body(gzip.clone()).decode_content("GZIP").unwrap(),
+            b"hello".as_slice()
+        );
+        assert_eq!(
+            body(zlib)
+                .decode_content("x-gzip, identity,deflate")
+                .unwrap(),
+            b"hello".as_slice()
+        );
+        assert_eq!(
+            body(deflate).decode_content("deflate").unwrap(),
+            b"hello".as_slice()
+        );
+
+        assert!(body(b"hello".to_vec()).decode_content("gzip").is_err());
+        assert_eq!(
+            body(gzip)
+                .decode_content("compress")
+                .unwrap_err()
+                .to_string(),
+            "parsing error: unsupported content encoding: compress"
+        );
+        #[cfg(not(feature = "brotli"))]
+        assert!(body(Vec::new()).decode_content("br").is_err());
+    }
+
+    #[test]
+    fn test_response_content_range() {
+        let range = |value: &str| {
+            parse_response(
+                format!("HTTP/1.1 206 OK\r\nContent-Range: {value}\r\nContent-Length: 0\r\n\r\n")
+                    .as_bytes(),
+            )
+            .unwrap()
+            .content_range()
+        };
+
+        let content_range = range("bytes 0-499/1234").unwrap();
+        assert_eq!(content_range.start_span, 38..39);
+        assert_eq!(content_range.end_span, 40..43);
+        assert_eq!(content_range.total_span.unwrap(), 44..48);
+
+        let content_range = range("bytes 500-999/*").unwrap();
+        assert_eq!(
+            (content_range.start, content_range.end, content_range.total),
+            (500, 999, None)
+        );
+        assert!(content_range.total_span.is_none());
+
+        assert!(range("bytes */1234").is_none());
+        assert!(range("items 0-1/2").is_none());
+        assert!(range("bytes 1-0/2").is_none());
+        assert!(range("bytes 0-2/2").is_none());
+        assert!(range("bytes 0-1").is_none());
+        assert!(range("bytes 0-a/2").is_none());
+
+        assert!(parse_response(b"HTTP/1.1 204 No Content\r\n\r\n")
+            .unwrap()
+            .content_range()
+            .is_none());
+    }
+
+    #[test]
+    fn test_request_accept() {
+        let req = parse_request(
+            b"GET / HTTP/1.1\r\nAccept: */*;q=0.1, text/plain ; charset=utf-8 ; Q=0.5\r\n\
+            Accept: text/html;q=2,,image/png;q=0.1234;level=1,a/b;q=1.000\r\n\r\n",
+        )
+        .unwrap();
+
+        let accept = req.accept();
+        let media = accept
+            .iter()
+            .map(|(span, _)| span.as_str())
+            .collect::<Vec<_>>();
+        let quality = accept.iter().map(|(_, q)| *q).collect::<Vec<_>>();
+
+        assert_eq!(
+            media,
+            vec!["*/*", "text/plain", "text/html", "image/png", "a/b"]
+        );
+        assert_eq!(quality, vec![Some(0.1), Some(0.5), None, None, Some(1.0)]);
+        assert_eq!(accept[1].0, 35..45);
+
+        let sorted = req
+            .accept_sorted()
+            .into_iter()
+            .map(|(span, _)| span.as_str().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sorted,
+            vec!["text/html", "image/png", "a/b", "text/plain", "*/*"]
+        );
+
+        assert!(parse_request(b"GET / HTTP/1.1\r\n\r\n")
+            .unwrap()
+            .accept()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_request_validate_host() {
+        let validate = |src: &[u8]| parse_request(src).unwrap().validate_host();
+
+        assert!(validate(b"GET http://Example.com/ HTTP/1.1\r\nHost: example.COM\r\n\r\n").is_ok());
+        assert!(validate(b"GET http://u@[::1]:8080/ HTTP/1.1\r\nHost: [::1]:8080\r\n\r\n").is_ok());
+        assert!(validate(b"GET http://example.com/ HTTP/1.1\r\n\r\n").is_ok());
+
+        assert_eq!(
+            validate(b"GET http://example.com/ HTTP/1.1\r\nHost: evil.com\r\n\r\n")
+                .unwrap_err()
+                .to_string(),
+            "parsing error: Host header \"evil.com\" does not match request target authority \"example.com\""
+        );
+        // Omitted ports are the default port of the scheme.
+        assert!(
+            validate(b"GET http://example.com/ HTTP/1.1\r\nHost: example.com:80\r\n\r\n").is_ok()
+        );
+        assert!(
+            validate(b"GET HTTPS://example.com:443/ HTTP/1.1\r\nHost: example.com\r\n\r\n").is_ok()
+        );
+        assert!(
+            validate(b"GET http://example.com/ HTTP/1.1\r\nHost: example.com:443\r\n\r\n").is_err()
+        );
+        assert!(
+            validate(b"GET https://example.com/ HTTP/1.1\r\nHost: example.com:80\r\n\r\n").is_err()
+        );
+        assert!(
+            validate(b"GET ws://example.com/ HTTP/1.1\r\nHost: example.com:80\r\n\r\n").is_err()
+        );
+        assert!(validate(b"GET / HTTP/1.1\r\nHost: a.com\r\nHost: b.com\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn test_request_body_length_ambiguity() {
+        let src =
+            b"POST / HTTP/1.1\r\nContent-Length: 4\r\nHost: a\r\ncontent-length: 5\r\n\r\nbody";
+        assert!(parse_request(src).is_err());
+
+        let req = parse_request_head(src).unwrap();
+        assert!(req.body.is_none());
+
+        let Some(BodyLengthConflict::ContentLength(headers)) = req.body_length_ambiguity() else {
+            panic!("expected a Content-Length conflict");
+        };
+        assert_eq!(
+            headers.iter().map(|h| h.span().clone()).collect::<Vec<_>>(),
+            [req.headers[0].span.clone(), req.headers[2].span.clone()]
+        );
+        assert_eq!(headers[0].span(), 17..36);
+
+        // Values are compared numerically.
+        let req = parse_request_head(
+            b"POST / HTTP/1.1\r\nContent-Length: 4\r\nContent-Length:  004 \r\n\r\nbody",
+        )
+        .unwrap();
+        assert!(req.body_length_ambiguity().is_none());
+
+        let req = parse_request_head(
+            b"POST / HTTP/1.1\r\nContent-Length: 4\r\nContent-Length: 4x\r\n\r\nbody",
+        )
+        .unwrap();
+        assert!(matches!(
+            req.body_length_ambiguity(),
+            Some(BodyLengthConflict::ContentLength(_))
+        ));
+
+        let src = b"POST / HTTP/1.1\r\nContent-Length: 0\r\nTransfer-Encoding: chunked\r\n\r\n";
+        assert!(parse_request(src).is_err());
+        let req = parse_request_head(src).unwrap();
+
+        let Some(BodyLengthConflict::TransferEncoding {
+            content_length,
+            transfer_encoding,
+        }) = req.body_length_ambiguity()
+        else {
+            panic!("expected a Transfer-Encoding conflict");
+        };
+        assert_eq!(content_length, [req.headers[0].clone()]);
+        assert_eq!(transfer_encoding, [req.headers[1].clone()]);
+
+        assert!(parse_request(b"GET / HTTP/1.1\r\n\r\n")
+            .unwrap()
+            .body_length_ambiguity()
+            .is_none());
+    }
+
+    #[test]
+    fn test_body_as_json() {
+        let src =
+            b"HTTP/1.1 200 OK\r\nContent-Length: 29\r\n\r\n{\"user\": {\"id\": 7, \"n\": \"x\"}}";
+        let resp = parse_response(src).unwrap();
+        let value = resp.body.as_ref().unwrap().as_json().unwrap();
+
+        let id = value.get("user.id").unwrap();
+        assert_eq!(id.span(), "7");
+        assert_eq!(id.span().indices(), &(55..56));
+        assert_eq!(&src[55..56], b"7");
+
+        let n = value.get("user.n").unwrap();
+        assert_eq!(&src[n.span().indices().min().unwrap()..][..1], b"x");
+
+        let src = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+        let resp = parse_response(src).unwrap();
+        assert!(resp.body.unwrap().as_json().is_err());
+
+        // The concatenated bytes are valid JSON, but the indices of a disjoint body can
+        // not be recovered by shifting.
+        let body = Body {
+            span: Span::from_ranges(Bytes::from_static(b"[1, xx 2]"), [0..3, 6..9]),
+        };
+        assert_eq!(body.as_bytes(), b"[1, 2]");
+        assert!(body.as_json().is_err());
+    }
+
+    #[test]
+    fn test_header_http_date() {
+        let date = |value: &'static str| {
+            let src = Bytes::from(format!("Date: {value}\r\n"));
+            let header = Header {
+                span: Span::new_bytes(&src, 0..src.len()),
+                name: HeaderName(Span::new_str(&src, 0..4)),
+                value: HeaderValue(Span::new_bytes(&src, 5..src.len() - 2)),
+            };
+            header.http_date().map(|date| {
+                [date.weekday, date.day, date.month, date.year, date.time]
+                    .map(|span| (span.as_str().to_string(), span.indices().min().unwrap()))
+            })
+        };
+
+        let parts = |parts: [(&str, usize); 5]| Some(parts.map(|(s, i)| (s.to_string(), i)));
+
+        assert_eq!(
+            date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            parts([
+                ("Sun", 6),
+                ("06", 11),
+                ("Nov", 14),
+                ("1994", 18),
+                ("08:49:37", 23)
+            ])
+        );
+        assert_eq!(
+            date("Sunday, 06-Nov-94 08:49:37 GMT"),
+            parts([
+                ("Sunday", 6),
+                ("06", 14),
+                ("Nov", 17),
+                ("94", 21),
+                ("08:49:37", 24)
+            ])
+        );
+        assert_eq!(
+            date("Sun Nov  6 08:49:37 1994"),
+            parts([
+                ("Sun", 6),
+                ("6", 15),
+                ("Nov", 10),
+                ("1994", 26),
+                ("08:49:37", 17)
+            ])
+        );
+        assert_eq!(date("Sun Nov 16 08:49:37 1994").unwrap()[1].0, "16");
+
+        assert!(date("Sun, 06 Nov 1994 08:49:37 UTC").is_none());
+        assert!(date("Sun, 6 Nov 1994 08:49:37 GMT").is_none());
+        assert!(date("Sunday, 06 Nov 1994 08:49:37 GMT").is_none());
+        assert!(date("Sun, 06 Foo 1994 08:49:37 GMT").is_none());
+        assert!(date("Sun, 06 Nov 1994 08-49-37 GMT").is_none());
+        assert!(date("Fooday, 06-Nov-94 08:49:37 GMT").is_none());
+        assert!(date("1994-11-06T08:49:37Z").is_none());
+        assert!(date("").is_none());
+        assert!(date("Sé, 06 Nov 1994 08:49:37 GMT").is_none());
+    }
+
+    #[test]
+    fn test_header_value_as_str() {
+        let req =
+            parse_request(b"GET / HTTP/1.1\r\nX-A: caf\xc3\xa9\r\nX-B: caf\xe9\r\n\r\n").unwrap();
+
+        assert_eq!(req.headers[0].value.as_str().unwrap(), "café");
+        assert!(req.headers[1].value.as_str().is_err());
+        assert_eq!(req.headers[1].value.as_bytes(), b"caf\xe9");
+    }
+
+    #[test]
+    fn test_find_header() {
+        let req = parse_request(
+            b"GET / HTTP/1.1\r\nAccept: text/html\r\nAccept-Encoding: gzip\r\nX-A: gzip\r\n\r\n",
+        )
+        .unwrap();
+
+        let header = req
+            .find_header(|h| h.value.as_bytes().windows(4).any(|w| w == b"gzip"))
+            .unwrap();
+        assert_eq!(header.name.as_str(), "Accept-Encoding");
+        assert!(req.find_header(|h| h.name.as_str() == "Host").is_none());
+
+        let res =
+            parse_response(b"HTTP/1.1 200 OK\r\nX-A: 1\r\nX-B: 2\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+        let header = res
+            .find_header(|h| h.name.as_str().starts_with("X-"))
+            .unwrap();
+        assert_eq!(header.value.as_bytes(), b"1");
+    }
+
+    #[test]
+    fn test_separator_span() {
+        let req = parse_request(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n").unwrap();
+        assert_eq!(req.separator_span(), b"\r\n\r\n".as_slice());
+        assert_eq!(req.separator_span().indices(), &(23..27));
+
+        let req = parse_request(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(req.separator_span(), b"\r\n".as_slice());
+        assert_eq!(req.separator_span().indices(), &(16..18));
+
+        let src = b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\nhi";
+        let mut req = parse_request(src).unwrap();
+        assert_eq!(req.separator_span().indices(), &(34..38));
+        req.offset(10);
+        assert_eq!(req.separator_span().indices(), &(44..48));
+
+        let res = parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nhi").unwrap();
+        assert_eq!(res.separator_span(), b"\r\n\r\n".as_slice());
+        assert_eq!(res.separator_span().indices(), &(34..38));
+
+        let res = parse_response(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+        assert_eq!(res.separator_span(), b"\r\n".as_slice());
+    }
+
+    #[test]
+    fn test_request_forwarded_for() {
+        let req = parse_request(
+            b"GET / HTTP/1.1\r\nX-Forwarded-For:  a ,, b\r\nHost: x\r\nx-forwarded-for: unknown\r\n\r\n",
+        )
+        .unwrap();
+
+        let chain = req.forwarded_for();
+        assert_eq!(chain, ["a", "b", "unknown"]);
+        assert_eq!(chain[0].indices(), &(34..35));
+        assert_eq!(chain[1].indices(), &(39..40));
+
+        let req = parse_request(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n").unwrap();
+        assert!(req.forwarded_for().is_empty());
+    }
+
+    #[test]
+    fn test_request_forwarded() {
+        let req = parse_request(
+            b"GET / HTTP/1.1\r\nForwarded: for=a; proto = http ;by=\"x,y;z\", ,for=b;secret\r\nForwarded: For=\"\"\r\n\r\n",
+        )
+        .unwrap();
+
+        let hops = req
+            .forwarded()
+            .into_iter()
+            .map(|hop| {
+                hop.into_iter()
+                    .map(|(name, value)| (name.as_str().to_string(), value.as_str().to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let pair = |name: &str, value: &str| (name.to_string(), value.to_string());
+
+        assert_eq!(
+            hops,
+            vec![
+                vec![pair("for", "a"), pair("proto", "http"), pair("by", "x,y;z")],
+                vec![pair("for", "b")],
+                vec![pair("For", "")],
+            ]
+        );
+
+        let hops = req.forwarded();
+        assert_eq!(hops[0][2].1.indices(), &(52..57));
+        assert_eq!(hops[1][0].0.indices(), &(61..64));
+
+        let req = parse_request(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n").unwrap();
+        assert!(req.forwarded().is_empty());
+    }
+
+    #[test]
+    fn test_request_fields_within() {
+        let src = b"POST /a HTTP/1.1\r\nHost: a\r\nX-Token: t0k3n\r\nContent-Length: 2\r\n\r\nhi";
+        let req = parse_request(src).unwrap();
+
+        let all = req.fields_within(&RangeSet::from(0..src.len()));
+        assert_eq!(all.len(), 9);
+        assert_eq!(all[0], FieldRef::Method(&req.request.method));
+        assert_eq!(all[8], FieldRef::Body(req.body.as_ref().unwrap()));
+
+        // Authorize the request line and the `Host` and `Content-Length` headers.
+        let host = req.headers[0].span.indices().clone();
+        let length = req.headers[2].span.indices().clone();
+        let authorized = RangeSet::from(0..18).union(&host).union(&length);
+
+        let fields = req.fields_within(&authorized);
+        assert_eq!(
+            fields,
+            vec![
+                FieldRef::Method(&req.request.method),
+                FieldRef::Target(&req.request.target),
+                FieldRef::HeaderName(&req.headers[0].name),
+                FieldRef::HeaderValue(&req.headers[0].value),
+                FieldRef::HeaderName(&req.headers[2].name),
+                FieldRef::HeaderValue(&req.headers[2].value),
+            ]
+        );
+
+        // A partially authorized field is not covered.
+        let authorized = RangeSet::from(vec![0..4, 5..6]);
+        assert_eq!(
+            req.fields_within(&authorized),
+            vec![FieldRef::Method(&req.request.method)]
+        );
+
+        assert!(req.fields_within(&RangeSet::default()).is_empty());
+    }
+
+    #[test]
+    fn test_response_is_informational() {
+        let res = parse_response(b"HTTP/1.1 100 Continue\r\n\r\n").unwrap();
+        assert!(res.is_informational());
+
+        let res = parse_response(b"HTTP/1.1 103 Early Hints\r\nLink: </a.css>\r\n\r\n").unwrap();
+        assert!(res.is_informational());
+
+        let res = parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+        assert!(!res.is_informational());
+    }
+
+    #[test]
+    fn test_offset() {
+        let req =
+            parse_request(b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 2\r\n\r\nhi").unwrap();
+
+        let mut shifted = req.clone();
+        shifted.offset(0);
+        assert_eq!(shifted, req);
+
+        shifted.offset(5);
+        assert_eq!(shifted.headers[1].value.span().indices(), &(47..48));
+        assert_eq!(shifted.body.unwrap().span().indices(), &(52..54));
+    }
+
+    #[test]
+    fn test_indices() {
+        let mut req = parse_request(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n").unwrap();
+        assert_eq!(req.indices(), &RangeSet::from(0..27));
+        req.offset(3);
+        assert_eq!(req.indices(), req.span().indices());
+        assert_eq!(req.indices(), &RangeSet::from(3..30));
+
+        let res = parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+        assert_eq!(res.indices(), &RangeSet::from(0..38));
+    }
+
+    #[test]
+    fn test_source() {
+        let src = b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\nhi";
+        let req = parse_request(src).unwrap();
+        assert_eq!(req.source().as_ref(), src);
+
+        // The second request of a stream retains the entire stream, so its indices
+        // index directly into the source.
+        let src = b"GET /a HTTP/1.1\r\n\r\nGET /b HTTP/1.1\r\nHost: x\r\n\r\n";
+        let req = crate::http::Requests::new_from_slice(src)
+            .nth(1)
+            .unwrap()
+            .unwrap();
+        let source = req.source();
+        assert_eq!(source.as_ref(), src);
+
+        let target = req.request.target.span().indices().min().unwrap();
+        assert_eq!(source[target], b'/');
+        assert_eq!(source[target + 1], b'b');
+
+        // A compacted request only retains its own bytes.
+        let req = req.compact();
+        assert_eq!(req.source().as_ref(), b"GET /b HTTP/1.1\r\nHost: x\r\n\r\n");
+
+        let res = parse_response(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+        assert_eq!(res.source().as_ref(), b"HTTP/1.1 204 No Content\r\n\r\n");
+    }
+
+    #[test]
+    fn test_headers_span() {
+        let req =
+            parse_request(b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 4\r\n\r\nbody").unwrap();
+
+        assert_eq!(
+            req.headers_span(),
+            b"Host: a\r\nContent-Length: 4\r\n\r\n".as_slice()
+        );
+        assert_eq!(req.headers_span(), 17..47);
+
+        let res = parse_response(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+
+        assert_eq!(res.headers_span(), b"\r\n".as_slice());
+        assert_eq!(res.headers_span(), 25..27);
+    }
+
+    #[test]
+    fn test_request_compact() {
+        let first = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let second = b"POST /hello HTTP/1.1\r\nContent-Length: 4\r\n\r\nping";
+        let src = [first.as_slice(), second.as_slice()].concat();
+
+        let reqs = crate::http::Requests::new_from_slice(&src)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let req = reqs.into_iter().nth(1).unwrap().compact();
+
+        assert_eq!(req, parse_request(second).unwrap());
+        assert_eq!(req.body.unwrap().span(), 43..47);
+    }
+
+    #[test]
+    fn test_response_compact() {
+        let first = b"HTTP/1.1 204 No Content\r\n\r\n";
+        let second = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\npong";
+        let src = [first.as_slice(), second.as_slice()].concat();
+
+        let resps = crate::http::Responses::new_from_slice(&src)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let resp = resps.into_iter().nth(1).unwrap().compact();
+
+        assert_eq!(resp, parse_response(second).unwrap());
+    }
+
+    #[test]
+    fn test_request_head_span() {
+        let src = b"POST / HTTP/1.1\r\nContent-Length: 4\r\n\r\nping";
+        let req = parse_request(src).unwrap();
+
+        let head = req.head_span();
+        assert_eq!(head, 0..src.len() - 4);
+        assert_eq!(head.as_bytes(), &src[..src.len() - 4]);
+
+        let req = parse_request(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(&req.head_span(), req.span());
+    }
+
+    #[test]
+    fn test_response_head_span() {
+        let src = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\npong";
+        let resp = parse_response(src).unwrap();
+
+        assert_eq!(resp.head_span().as_bytes(), &src[..src.len() - 4]);
+    }
+}
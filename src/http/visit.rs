@@ -0,0 +1,102 @@
+use super::types::{
+    Body, Code, Header, HeaderName, HeaderValue, Method, Reason, Request, RequestLine, Response,
+    Status, Target,
+};
+
+/// A visitor for HTTP requests and responses.
+///
+/// # Example
+///
+/// ```
+/// use spansy::http::{parse_request, HeaderValue, HttpVisit};
+/// use spansy::Spanned;
+///
+/// struct ValueRedactor<'a> {
+///     src: &'a mut Vec<u8>,
+/// }
+///
+/// impl HttpVisit for ValueRedactor<'_> {
+///     fn visit_header_value(&mut self, node: &HeaderValue) {
+///         for range in node.span().indices().iter_ranges() {
+///             self.src[range].fill(b'X');
+///         }
+///     }
+/// }
+///
+/// let src = b"GET / HTTP/1.1\r\nHost: localhost\r\nCookie: secret\r\n\r\n";
+///
+/// let request = parse_request(src).unwrap();
+///
+/// let mut new = src.to_vec();
+///
+/// // Redact the values of all headers.
+/// ValueRedactor { src: &mut new }.visit_request(&request);
+///
+/// assert_eq!(new, b"GET / HTTP/1.1\r\nHost: XXXXXXXXX\r\nCookie: XXXXXX\r\n\r\n");
+/// ```
+pub trait HttpVisit {
+    /// Visit a request.
+    fn visit_request(&mut self, node: &Request) {
+        self.visit_request_line(&node.request);
+
+        for header in &node.headers {
+            self.visit_header(header);
+        }
+
+        if let Some(body) = &node.body {
+            self.visit_body(body);
+        }
+    }
+
+    /// Visit a response.
+    fn visit_response(&mut self, node: &Response) {
+        self.visit_status(&node.status);
+
+        for header in &node.headers {
+            self.visit_header(header);
+        }
+
+        if let Some(body) = &node.body {
+            self.visit_body(body);
+        }
+    }
+
+    /// Visit a request line.
+    fn visit_request_line(&mut self, node: &RequestLine) {
+        self.visit_method(&node.method);
+        self.visit_target(&node.target);
+    }
+
+    /// Visit a response status.
+    fn visit_status(&mut self, node: &Status) {
+        self.visit_code(&node.code);
+        self.visit_reason(&node.reason);
+    }
+
+    /// Visit a header.
+    fn visit_header(&mut self, node: &Header) {
+        self.visit_header_name(&node.name);
+        self.visit_header_value(&node.value);
+    }
+
+    /// Visit a request method.
+    fn visit_method(&mut self, _node: &Method) {}
+
+    /// Visit a request target.
+    fn visit_target(&mut self, _node: &Target) {}
+
+    /// Visit a response code.
+    fn visit_code(&mut self, _node: &Code) {}
+
+    /// Visit a response reason phrase.
+    fn visit_reason(&mut self, _node: &Reason) {}
+
+    /// Visit a header name.
+    fn visit_header_name(&mut self, _node: &HeaderName) {}
+
+    /// Visit a header value.
+    fn visit_header_value(&mut self, _node: &HeaderValue) {}
+
+    /// Visit a body.
+    fn visit_body(&mut self, _node: &Body) {}
+}
@@ -5,7 +5,7 @@
 //!
 //! Note that the parser does *not* fully parse values, it simply computes the span of the corresponding
 //! characters in the source string. Thus, this parser should not be expected to perform any kind of
-//! validation of the JSON.
+//! validation of the JSON. Use [`parse_str_strict`] if additional validation is required.
 //!
 //! # Example
 //!
@@ -29,6 +29,132 @@ mod span;
 mod types;
 mod visit;
 
-pub use span::{parse, parse_slice, parse_str};
+pub use span::{
+    parse, parse_from_bytes, parse_reader, parse_slice, parse_str, parse_str_exact,
+    parse_str_jsonc, parse_str_strict, parse_str_with_depth, parse_stream,
+};
 pub use types::{Array, Bool, JsonKey, JsonValue, KeyValue, Null, Number, Object, String};
-pub use visit::JsonVisit;
+pub use visit::{JsonVisit, JsonVisitControl, JsonVisitPath, PathSegment};
+
+use bytes::Bytes;
+
+use crate::ParseError;
+
+use self::span::parse_range;
+
+/// An iterator yielding JSON values from newline-delimited JSON (NDJSON) source.
+///
+/// Each non-blank line is parsed as a separate JSON value, and blank lines are skipped.
+/// The spans of the yielded values are absolute within the source.
+///
+/// # Example
+///
+/// ```
+/// use spansy::{json::Values, Spanned};
+///
+/// let src = b"{\"foo\": 1}\n\n{\"bar\": 2}\n";
+///
+/// let values = Values::new_from_slice(src)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(values.len(), 2);
+/// assert_eq!(values[1].get("bar").unwrap().span().indices(), 20..21);
+/// ```
+#[derive(Debug)]
+pub struct Values {
+    src: Bytes,
+    /// The current position in the source string.
+    pos: usize,
+}
+
+impl Values {
+    /// Returns a new `Values` iterator.
+    pub fn new(src: Bytes) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    /// Returns a new `Values` iterator.
+    pub fn new_from_slice(src: &[u8]) -> Self {
+        Self {
+            src: Bytes::copy_from_slice(src),
+            pos: 0,
+        }
+    }
+}
+
+impl Iterator for Values {
+    type Item = Result<JsonValue, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.src.len() {
+            let line_end = self.src[self.pos..]
+                .iter()
+                .position(|b| *b == b'\n')
+                .map(|idx| self.pos + idx)
+                .unwrap_or(self.src.len());
+
+            let line = &self.src[self.pos..line_end];
+            let start = line.iter().position(|b| !is_whitespace(b));
+            let end = line.iter().rposition(|b| !is_whitespace(b));
+
+            let line_start = self.pos;
+            self.pos = line_end + 1;
+
+            // Skip blank lines.
+            if let (Some(start), Some(end)) = (start, end) {
+                return Some(parse_range(
+                    &self.src,
+                    line_start + start..line_start + end + 1,
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns `true` if the byte is insignificant JSON whitespace.
+fn is_whitespace(b: &u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Spanned;
+
+    use super::*;
+
+    const NDJSON: &[u8] = b"{\"foo\": \"bar\"}\n\
+        \r\n\
+        [1, 2, 3]\r\n\
+        \n\
+        {\"baz\": {\"quux\": 42}}";
+
+    #[test]
+    fn test_parse_values() {
+        let values = Values::new_from_slice(NDJSON)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(values.len(), 3);
+
+        assert_eq!(values[0].get("foo").unwrap().span(), "bar");
+        assert_eq!(values[1].span(), "[1, 2, 3]");
+        assert_eq!(values[1].span().indices(), 17..26);
+        assert_eq!(values[2].get("baz.quux").unwrap().span(), "42");
+        assert_eq!(
+            values[2].get("baz.quux").unwrap().span().indices(),
+            NDJSON.len() - 4..NDJSON.len() - 2
+        );
+    }
+
+    #[test]
+    fn test_parse_values_error_advances() {
+        let mut values = Values::new_from_slice(b"{\"foo\": }\n[1]\n");
+
+        assert!(values.next().unwrap().is_err());
+        assert_eq!(values.next().unwrap().unwrap().span(), "[1]");
+        assert!(values.next().is_none());
+    }
+}
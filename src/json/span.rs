@@ -1,36 +1,162 @@
+use std::{collections::HashSet, ops::Range};
+
 use bytes::Bytes;
 use pest::{iterators::Pair as PestPair, Parser};
 use types::KeyValue;
 
-use super::types::{self, JsonValue};
+use super::{
+    types::{self, JsonValue},
+    JsonVisit,
+};
 
-use crate::{ParseError, Span};
+use crate::{ParseError, Source, Span, Spanned};
 
 #[derive(pest_derive::Parser)]
 #[grammar = "json/json.pest"]
 struct JsonParser;
 
+/// The default maximum nesting depth of arrays and objects.
+pub(crate) const MAX_DEPTH: usize = 128;
+
 /// Parse a JSON value from a source string.
+///
+/// Arrays and objects may be nested at most 128 levels deep, see [`parse_str_with_depth`]
+/// to configure this limit.
+///
+/// A leading UTF-8 byte order mark is skipped, and the spans of the returned value index
+/// into the source following it. Any other characters before or after the value,
+/// including whitespace, are an error.
 pub fn parse_str(src: &str) -> Result<JsonValue, ParseError> {
+    parse_str_with_depth(src, MAX_DEPTH)
+}
+
+/// Parse a JSON value from a source string, with the given maximum nesting depth of
+/// arrays and objects.
+///
+/// Returns an error if the depth is exceeded, which protects against exhausting the
+/// stack while parsing adversarial input.
+pub fn parse_str_with_depth(src: &str, max_depth: usize) -> Result<JsonValue, ParseError> {
     let src = Bytes::copy_from_slice(src.as_bytes());
 
     // # Safety
     // `src` was passed as a string slice, so it is guaranteed to be valid UTF-8.
     let src_str = unsafe { std::str::from_utf8_unchecked(src.as_ref()) };
 
-    let value = JsonParser::parse(Rule::value, src_str)?
-        .next()
-        .ok_or_else(|| ParseError("no json value is present in source".to_string()))?;
+    parse_value(&src, src_str, max_depth)
+}
 
-    // Since json.pest grammar prohibits leading characters but allows trailing
-    // characters, we prohibit trailing characters here.
-    if value.as_str().len() != src.len() {
-        return Err(ParseError(
-            "trailing characters are present in source".to_string(),
-        ));
+/// Parse a JSON value from a source string which may be followed by whitespace.
+///
+/// Unlike [`parse_str`], trailing whitespace after the value is accepted, such as the
+/// final newline of a file. Any other trailing characters are an error, which catches
+/// truncated or concatenated documents.
+///
+/// # Example
+///
+/// ```
+/// use spansy::{json::parse_str_exact, Spanned};
+///
+/// let value = parse_str_exact("{\"a\": 1}\n").unwrap();
+/// assert_eq!(value.span(), "{\"a\": 1}");
+///
+/// assert!(parse_str_exact("{\"a\": 1}}").is_err());
+/// ```
+pub fn parse_str_exact(src: &str) -> Result<JsonValue, ParseError> {
+    let src = Bytes::copy_from_slice(src.as_bytes());
+
+    // # Safety
+    // `src` was passed as a string slice, so it is guaranteed to be valid UTF-8.
+    let src_str = unsafe { std::str::from_utf8_unchecked(src.as_ref()) };
+
+    parse_value(
+        &src,
+        src_str.trim_end_matches([' ', '\t', '\r', '\n']),
+        MAX_DEPTH,
+    )
+}
+
+/// Parse a JSON value from a source string, rejecting input which is not well-formed JSON.
+///
+/// In addition to the checks performed by [`parse_str`], this rejects objects containing
+/// duplicate keys or with a trailing or missing comma between members, strings containing
+/// unescaped control characters, and escape sequences which do not decode to valid
+/// unicode, such as unpaired surrogates.
+pub fn parse_str_strict(src: &str) -> Result<JsonValue, ParseError> {
+    let value = parse_str(src)?;
+
+    let mut validator = StrictValidator { err: None };
+    validator.visit_value(&value);
+
+    match validator.err {
+        Some(err) => Err(err),
+        None => Ok(value),
+    }
+}
+
+/// Parse a JSON value from a source string which may contain comments.
+///
+/// Both line (`// ...`) and block (`/* ... */`) comments are supported, and may be
+/// surrounded by whitespace before or after the value. Comment delimiters inside strings
+/// are not treated as comments.
+///
+/// The spans of the returned value index into the original source, including comments.
+pub fn parse_str_jsonc(src: &str) -> Result<JsonValue, ParseError> {
+    let src = Bytes::copy_from_slice(src.as_bytes());
+    let stripped = Bytes::from(strip_comments(&src)?);
+
+    // # Safety
+    // Comments are replaced byte for byte with spaces, which preserves the UTF-8
+    // validity of the source string.
+    let stripped_str = unsafe { std::str::from_utf8_unchecked(stripped.as_ref()) };
+
+    let stripped_str = stripped_str.trim_matches([' ', '\t', '\r', '\n']);
+    if stripped_str.is_empty() {
+        return Err(ParseError("no json value is present in source".to_string()));
     }
 
-    Ok(JsonValue::from_pair(src.clone(), value))
+    // The stripped source has the same length as the original, so the spans are rebuilt
+    // from the original source at the same indices.
+    Ok(parse_value(&stripped, stripped_str, MAX_DEPTH)?.compact(&src, 0))
+}
+
+/// Returns a copy of the source with all comments replaced by spaces.
+fn strip_comments(src: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let mut out = src.to_vec();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut pos = 0;
+    while pos < src.len() {
+        match (src[pos], src.get(pos + 1)) {
+            _ if escaped => escaped = false,
+            (b'\\', _) if in_string => escaped = true,
+            (b'"', _) => in_string = !in_string,
+            (b'/', Some(b'/')) if !in_string => {
+                let end = src[pos..]
+                    .iter()
+                    .position(|b| *b == b'\n')
+                    .map_or(src.len(), |idx| pos + idx);
+                out[pos..end].fill(b' ');
+                pos = end;
+                continue;
+            }
+            (b'/', Some(b'*')) if !in_string => {
+                let end = src[pos + 2..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map(|idx| pos + 2 + idx + 2)
+                    .ok_or_else(|| {
+                        ParseError(format!("unterminated block comment at index {pos}"))
+                    })?;
+                out[pos..end].fill(b' ');
+                pos = end;
+                continue;
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+
+    Ok(out)
 }
 
 /// Parse a JSON value from a byte slice.
@@ -39,9 +165,163 @@ pub fn parse_slice(src: &[u8]) -> Result<JsonValue, ParseError> {
     parse(src)
 }
 
+/// Parse a JSON value from a reader.
+///
+/// The reader is read to EOF before parsing, so this does not return until the reader is
+/// exhausted.
+pub fn parse_reader<R: std::io::Read>(mut reader: R) -> Result<JsonValue, ParseError> {
+    let mut src = Vec::new();
+    reader
+        .read_to_end(&mut src)
+        .map_err(|err| ParseError(format!("failed to read json source: {err}")))?;
+
+    parse(Bytes::from(src))
+}
+
+/// Parse a stream of JSON values which are concatenated without a delimiter, such as
+/// `{"a":1}{"b":2}`.
+///
+/// Insignificant whitespace between values is skipped. Unlike [`Values`](super::Values),
+/// values do not need to be separated by newlines. The spans of the yielded values are
+/// absolute within the source.
+///
+/// The iterator stops after the first error, as the start of the next value can not be
+/// determined. A trailing partial value is an error.
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use spansy::{json::parse_stream, Spanned};
+///
+/// let values = parse_stream(Bytes::from_static(b"{\"a\":1}{\"b\":2} [3]"))
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(values.len(), 3);
+/// assert_eq!(values[1].get("b").unwrap().span().indices(), 12..13);
+/// ```
+pub fn parse_stream(src: Bytes) -> impl Iterator<Item = Result<JsonValue, ParseError>> {
+    // Invalid UTF-8 is only an error once a value reaches it, so values preceding it are
+    // still yielded.
+    let valid = match std::str::from_utf8(&src) {
+        Ok(_) => src.len(),
+        Err(err) => err.valid_up_to(),
+    };
+
+    let mut pos = 0;
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        pos += src[pos..]
+            .iter()
+            .position(|b| !matches!(b, b' ' | b'\t' | b'\r' | b'\n'))
+            .unwrap_or(src.len() - pos);
+        if pos == src.len() {
+            return None;
+        }
+
+        match parse_prefix(&src, valid, pos) {
+            Ok((value, end)) => {
+                pos = end;
+                Some(Ok(value))
+            }
+            Err(err) => {
+                done = true;
+                Some(Err(err))
+            }
+        }
+    })
+}
+
+/// Parse the JSON value at the start of the source bytes following `pos`, ignoring any
+/// characters after it.
+///
+/// The source must be valid UTF-8 up to `valid`, and the value must end before it.
+///
+/// Returns the value and the index following it.
+fn parse_prefix(src: &Bytes, valid: usize, pos: usize) -> Result<(JsonValue, usize), ParseError> {
+    if pos >= valid {
+        return Err(ParseError(format!(
+            "invalid utf-8 sequence at index {valid}"
+        )));
+    }
+
+    // # Safety
+    // The source was validated as UTF-8 up to `valid`, and `pos` follows whitespace or a
+    // complete value so it is on a character boundary.
+    let src_str = unsafe { std::str::from_utf8_unchecked(&src[pos..valid]) };
+
+    // Only arrays and objects can be nested, and the check stops at the end of the value.
+    if src_str.starts_with(['[', '{']) {
+        check_depth_prefix(src_str.as_bytes(), MAX_DEPTH)?;
+    }
+
+    let value = JsonParser::parse(Rule::value, src_str)?
+        .next()
+        .ok_or_else(|| ParseError("no json value is present in source".to_string()))?;
+
+    // The string rule excludes the quotes, so the value ends after the closing quote.
+    let mut end = pos + value.as_span().end();
+    if value.as_rule() == Rule::string {
+        end += 1;
+    }
+
+    Ok((JsonValue::from_pair(src, value), end))
+}
+
 /// Parse a JSON value from source bytes.
 pub fn parse(src: Bytes) -> Result<JsonValue, ParseError> {
-    let src_str = std::str::from_utf8(&src)?;
+    parse_range(&src, 0..src.len())
+}
+
+/// Parse a JSON value from the source bytes starting at the given offset.
+///
+/// The value must extend to the end of `src`. The spans of the returned value are
+/// absolute within `src`, which keeps them aligned when the JSON is embedded in a larger
+/// message.
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use spansy::{json::parse_from_bytes, Spanned};
+///
+/// let src = Bytes::from_static(b"Content-Length: 8\r\n\r\n{\"a\": 1}");
+///
+/// let value = parse_from_bytes(&src, 21).unwrap();
+/// assert_eq!(value.get("a").unwrap().span().indices(), 27..28);
+/// ```
+pub fn parse_from_bytes(src: &Bytes, offset: usize) -> Result<JsonValue, ParseError> {
+    if offset > src.len() {
+        return Err(ParseError(format!(
+            "offset {offset} is out of bounds of source with length {}",
+            src.len()
+        )));
+    }
+
+    parse_range(src, offset..src.len())
+}
+
+/// Parse a JSON value from the given range of the source bytes.
+///
+/// The spans of the returned value are absolute within `src`.
+pub(crate) fn parse_range(src: &Bytes, range: Range<usize>) -> Result<JsonValue, ParseError> {
+    let src_str = std::str::from_utf8(&src[range])?;
+
+    parse_value(src, src_str, MAX_DEPTH)
+}
+
+/// Parse a JSON value from a string slice of the source bytes.
+fn parse_value(src: &Bytes, src_str: &str, max_depth: usize) -> Result<JsonValue, ParseError> {
+    // The spans are computed from the position of each token within `src`, so skipping
+    // the byte order mark does not change the indices of the value.
+    let src_str = src_str.strip_prefix('\u{FEFF}').unwrap_or(src_str);
+
+    check_depth(src_str.as_bytes(), max_depth)?;
 
     let value = JsonParser::parse(Rule::value, src_str)?
         .next()
@@ -49,38 +329,170 @@ pub fn parse(src: Bytes) -> Result<JsonValue, ParseError> {
 
     // Since json.pest grammar prohibits leading characters but allows trailing
     // characters, we prohibit trailing characters here.
-    if value.as_str().len() != src.len() {
+    if value.as_str().len() != src_str.len() {
         return Err(ParseError(
             "trailing characters are present in source".to_string(),
         ));
     }
 
-    Ok(JsonValue::from_pair(src.clone(), value))
+    Ok(JsonValue::from_pair(src, value))
+}
+
+/// Checks that arrays and objects are nested at most `max_depth` levels deep.
+///
+/// This is checked ahead of parsing as both the parser and the conversion of the
+/// parse tree are recursive.
+fn check_depth(src: &[u8], max_depth: usize) -> Result<(), ParseError> {
+    check_depth_inner(src, max_depth, false)
+}
+
+/// Checks that the array or object at the start of the source is nested at most
+/// `max_depth` levels deep, ignoring anything after it.
+fn check_depth_prefix(src: &[u8], max_depth: usize) -> Result<(), ParseError> {
+    check_depth_inner(src, max_depth, true)
+}
+
+fn check_depth_inner(src: &[u8], max_depth: usize, prefix: bool) -> Result<(), ParseError> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for b in src {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' if in_string => escaped = true,
+            b'"' => in_string = !in_string,
+            b'[' | b'{' if !in_string => {
+                depth += 1;
+                if depth > max_depth {
+                    return Err(ParseError(format!(
+                        "maximum nesting depth of {max_depth} exceeded"
+                    )));
+                }
+            }
+            b']' | b'}' if !in_string => {
+                depth = depth.saturating_sub(1);
+                if prefix && depth == 0 {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// A visitor which validates a JSON value, recording the first error.
+struct StrictValidator {
+    err: Option<ParseError>,
+}
+
+impl StrictValidator {
+    /// Validates a string, returning the decoded string.
+    fn validate_str(&mut self, span: &Span<str>) -> Option<std::string::String> {
+        if self.err.is_some() {
+            return None;
+        }
+
+        let start = span.indices().min().unwrap_or_default();
+
+        if let Some(idx) = span.as_str().bytes().position(|b| b < 0x20) {
+            self.err = Some(ParseError(format!(
+                "unescaped control character in string at index {}",
+                start + idx
+            )));
+            return None;
+        }
+
+        match types::unescape(span.as_str()) {
+            Ok(s) => Some(s),
+            Err(err) => {
+                self.err = Some(ParseError(format!("{} at index {start}", err.0)));
+                None
+            }
+        }
+    }
+}
+
+impl JsonVisit for StrictValidator {
+    fn visit_object(&mut self, node: &types::Object) {
+        let mut keys = HashSet::new();
+        for (idx, kv) in node.elems.iter().enumerate() {
+            if self.err.is_some() {
+                return;
+            }
+
+            // The grammar makes the comma following each member optional, so members must
+            // be followed by a comma except for the last.
+            let has_comma = kv.span.as_str().ends_with(',');
+            let end = kv.span.indices().max().unwrap_or_default();
+            if idx + 1 == node.elems.len() && has_comma {
+                self.err = Some(ParseError(format!(
+                    "trailing comma in object at index {end}"
+                )));
+                return;
+            } else if idx + 1 < node.elems.len() && !has_comma {
+                self.err = Some(ParseError(format!(
+                    "missing comma between object members at index {}",
+                    end + 1
+                )));
+                return;
+            }
+
+            let Some(key) = self.validate_str(kv.key.span()) else {
+                return;
+            };
+
+            if !keys.insert(key) {
+                self.err = Some(ParseError(format!(
+                    "duplicate object key \"{}\" at index {}",
+                    kv.key.span().as_str(),
+                    kv.key.span().indices().min().unwrap_or_default()
+                )));
+                return;
+            }
+
+            self.visit_value(&kv.value);
+        }
+    }
+
+    fn visit_string(&mut self, node: &types::String) {
+        self.validate_str(node.span());
+    }
 }
 
 macro_rules! impl_from_pair {
     ($ty:ty, $rule:ident) => {
         impl $ty {
-            fn from_pair(src: Bytes, pair: PestPair<'_, Rule>) -> Self {
+            fn from_pair(src: &Bytes, pair: PestPair<'_, Rule>) -> Self {
                 assert!(matches!(pair.as_rule(), Rule::$rule));
 
                 Self(Span::new_from_str(src, pair.as_str()))
             }
         }
     };
+    ($ty:ty, $rule:ident, source) => {
+        impl $ty {
+            fn from_pair(src: &Bytes, pair: PestPair<'_, Rule>) -> Self {
+                assert!(matches!(pair.as_rule(), Rule::$rule));
+
+                Self(Span::new_from_str(src, pair.as_str()), Source(src.clone()))
+            }
+        }
+    };
 }
 
 impl_from_pair!(types::JsonKey, string);
-impl_from_pair!(types::Number, number);
-impl_from_pair!(types::Bool, bool);
-impl_from_pair!(types::Null, null);
-impl_from_pair!(types::String, string);
+impl_from_pair!(types::Number, number, source);
+impl_from_pair!(types::Bool, bool, source);
+impl_from_pair!(types::Null, null, source);
+impl_from_pair!(types::String, string, source);
 
 impl types::KeyValue {
-    fn from_pair(src: Bytes, pair: PestPair<'_, Rule>) -> Self {
+    fn from_pair(src: &Bytes, pair: PestPair<'_, Rule>) -> Self {
         assert!(matches!(pair.as_rule(), Rule::pair));
 
-        let span = Span::new_from_str(src.clone(), pair.as_str().trim_end());
+        let span = Span::new_from_str(src, pair.as_str().trim_end());
 
         let mut pairs = pair.into_inner();
 
@@ -88,43 +500,66 @@ impl types::KeyValue {
         let value = pairs.next().expect("value is present");
 
         Self {
+            // The trailing whitespace is only known from the layout of the object, and is
+            // set by the caller.
+            span_with_trailing: span.clone(),
             span,
-            key: types::JsonKey::from_pair(src.clone(), key),
-            value: types::JsonValue::from_pair(src.clone(), value),
+            key: types::JsonKey::from_pair(src, key),
+            value: types::JsonValue::from_pair(src, value),
         }
     }
 }
 
 impl types::Object {
-    fn from_pair(src: Bytes, pair: PestPair<'_, Rule>) -> Self {
+    fn from_pair(src: &Bytes, pair: PestPair<'_, Rule>) -> Self {
         assert!(matches!(pair.as_rule(), Rule::object));
 
+        let span = Span::new_from_str(src, pair.as_str());
+        let mut elems = pair
+            .into_inner()
+            .map(|pair| KeyValue::from_pair(src, pair))
+            .collect::<Vec<_>>();
+
+        // Each pair extends up to the start of the next pair, or the closing brace.
+        let start = |span: &Span<str>| span.indices.min().expect("span is not empty");
+        let mut end = span
+            .indices
+            .iter_ranges()
+            .last()
+            .expect("object is not empty")
+            .end
+            - 1;
+        for kv in elems.iter_mut().rev() {
+            let kv_start = start(&kv.span);
+            kv.span_with_trailing = Span::new_str(src, kv_start..end);
+            end = kv_start;
+        }
+
         Self {
-            span: Span::new_from_str(src.clone(), pair.as_str()),
-            elems: pair
-                .into_inner()
-                .map(|pair| KeyValue::from_pair(src.clone(), pair))
-                .collect(),
+            span,
+            source: Source(src.clone()),
+            elems,
         }
     }
 }
 
 impl types::Array {
-    fn from_pair(src: Bytes, pair: PestPair<'_, Rule>) -> Self {
+    fn from_pair(src: &Bytes, pair: PestPair<'_, Rule>) -> Self {
         assert!(matches!(pair.as_rule(), Rule::array));
 
         Self {
-            span: Span::new_from_str(src.clone(), pair.as_str()),
+            span: Span::new_from_str(src, pair.as_str()),
+            source: Source(src.clone()),
             elems: pair
                 .into_inner()
-                .map(|pair| types::JsonValue::from_pair(src.clone(), pair))
+                .map(|pair| types::JsonValue::from_pair(src, pair))
                 .collect(),
         }
     }
 }
 
 impl types::JsonValue {
-    fn from_pair(src: Bytes, pair: PestPair<'_, Rule>) -> Self {
+    fn from_pair(src: &Bytes, pair: PestPair<'_, Rule>) -> Self {
         match pair.as_rule() {
             Rule::object => Self::Object(types::Object::from_pair(src, pair)),
             Rule::array => Self::Array(types::Array::from_pair(src, pair)),
@@ -154,6 +589,269 @@ mod tests {
         assert_eq!(value.get("arr").unwrap().span(), "[1, 2, 3]");
     }
 
+    #[test]
+    fn test_parse_str_strict() {
+        let src = r#"{"foo": {"bar": "\u00e9"}, "baz": ["\ud83d\ude00"]}"#;
+        assert_eq!(parse_str_strict(src).unwrap(), parse_str(src).unwrap());
+
+        assert_eq!(
+            parse_str_strict(r#"{"foo": 1, "bar": {"a": 1, "\u0061": 2}}"#)
+                .err()
+                .unwrap()
+                .to_string(),
+            "parsing error: duplicate object key \"\\u0061\" at index 28"
+        );
+        assert_eq!(
+            parse_str_strict(r#"["foo", "\ud83d"]"#)
+                .err()
+                .unwrap()
+                .to_string(),
+            "parsing error: unpaired surrogate in string: \\ud83d at index 9"
+        );
+        assert_eq!(
+            parse_str_strict("[\"foo\tbar\"]")
+                .err()
+                .unwrap()
+                .to_string(),
+            "parsing error: unescaped control character in string at index 5"
+        );
+
+        assert_eq!(
+            parse_str_strict(r#"{"a":1,}"#).err().unwrap().to_string(),
+            "parsing error: trailing comma in object at index 6"
+        );
+        assert!(parse_str_strict(r#"{"a":1,"b":2,}"#).is_err());
+        assert!(parse_str_strict(r#"[{"a": {"b": 1 ,}}]"#).is_err());
+        assert_eq!(
+            parse_str_strict(r#"{"a":1 "b":2}"#)
+                .err()
+                .unwrap()
+                .to_string(),
+            "parsing error: missing comma between object members at index 6"
+        );
+        assert!(parse_str_strict(r#"{"a": {"b": 1 "c": 2}}"#).is_err());
+        assert!(parse_str_strict(r#"{"a": 1 , "b": [1, {"c": "d,"}] }"#).is_ok());
+
+        // The lenient parser accepts all of these.
+        assert!(parse_str(r#"{"a": 1, "a": 2}"#).is_ok());
+        assert!(parse_str(r#"{"a":1,}"#).is_ok());
+        assert!(parse_str(r#"{"a":1 "b":2}"#).is_ok());
+    }
+
+    #[test]
+    fn test_parse_str_with_depth() {
+        let src = format!("{}{}", "[".repeat(10_000), "]".repeat(10_000));
+        assert_eq!(
+            parse_str(&src).err().unwrap().to_string(),
+            "parsing error: maximum nesting depth of 128 exceeded"
+        );
+
+        let src = "{\"a\": [{\"b\": \"[[[[\"}]}";
+        assert!(parse_str_with_depth(src, 3).is_ok());
+        assert!(parse_str_with_depth(src, 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_str_jsonc() {
+        let src = "// header\n{\"a\": /* inline */ 1, \"b\": \"// not a comment\", /* é */ \"c\": [true]}\n// end";
+
+        let value = parse_str_jsonc(src).unwrap();
+
+        let start = src.find('{').unwrap();
+        let end = src.rfind('}').unwrap() + 1;
+        assert_eq!(value.span(), &src[start..end]);
+        assert_eq!(value.span().indices(), &(start..end));
+
+        let a = value.get("a").unwrap().span();
+        assert_eq!(a, "1");
+        assert_eq!(a.indices(), &(29..30));
+        assert_eq!(value.get("b").unwrap().span(), "// not a comment");
+        assert_eq!(value.get("c.0").unwrap().span(), "true");
+
+        assert!(parse_str(src).is_err());
+        assert_eq!(
+            parse_str_jsonc("{\"a\": 1 /* open")
+                .err()
+                .unwrap()
+                .to_string(),
+            "parsing error: unterminated block comment at index 8"
+        );
+        assert!(parse_str_jsonc("// nothing").is_err());
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        let src = b"{\"foo\": [1, 2]}";
+
+        let value = parse_reader(src.as_slice()).unwrap();
+        assert_eq!(value, parse_slice(src).unwrap());
+
+        struct FailingReader;
+
+        impl std::io::Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("connection reset"))
+            }
+        }
+
+        assert_eq!(
+            parse_reader(FailingReader).unwrap_err().to_string(),
+            "parsing error: failed to read json source: connection reset"
+        );
+    }
+
+    #[test]
+    fn test_parse_str_bom() {
+        let src = "\u{FEFF}{\"foo\": [1]}";
+
+        let value = parse_str(src).unwrap();
+        assert_eq!(value.span(), "{\"foo\": [1]}");
+        assert_eq!(value.span().indices(), &(3..src.len()));
+        assert_eq!(value.get("foo.0").unwrap().span().indices(), &(12..13));
+
+        assert_eq!(parse_slice(src.as_bytes()).unwrap(), value);
+        assert!(parse_str("\u{FEFF} 1").is_err());
+        assert!(parse_str("\u{FEFF}").is_err());
+    }
+
+    #[test]
+    fn test_parse_str_exact() {
+        let value = parse_str_exact("[1, {\"a\": 2}] \r\n\t").unwrap();
+        assert_eq!(value.span().indices(), &(0..13));
+
+        assert_eq!(
+            parse_str_exact("[1, {\"a\": 2}]}")
+                .err()
+                .unwrap()
+                .to_string(),
+            "parsing error: trailing characters are present in source"
+        );
+        assert!(parse_str_exact("{\"a\": 1} {\"b\": 2}").is_err());
+        assert!(parse_str_exact(" ").is_err());
+    }
+
+    #[test]
+    fn test_parse_from_bytes() {
+        let head = b"POST /api HTTP/1.1\r\nContent-Length: 25\r\n\r\n";
+        let src = Bytes::from([&head[..], b"{\"a\": [1, \"b\"], \"c\": {}}"].concat());
+
+        let value = parse_from_bytes(&src, head.len()).unwrap();
+        assert_eq!(value.span().indices(), &(head.len()..src.len()));
+
+        let b = value.get("a.1").unwrap();
+        assert_eq!(b.span(), "b");
+        assert_eq!(&src[b.span().indices().min().unwrap()..][..1], b"b");
+
+        assert!(parse_from_bytes(&src, 0).is_err());
+        assert!(parse_from_bytes(&src, src.len() + 1).is_err());
+    }
+
+    #[test]
+    fn test_number_syntax() {
+        // (source, as_i64, as_f64)
+        let valid: &[(&str, Option<i64>, f64)] = &[
+            ("0", Some(0), 0.0),
+            ("-0", Some(0), -0.0),
+            ("42", Some(42), 42.0),
+            ("-7", Some(-7), -7.0),
+            ("0.5", None, 0.5),
+            ("-0.5", None, -0.5),
+            ("1e3", None, 1e3),
+            ("1E+3", None, 1e3),
+            ("2.5e-1", None, 0.25),
+        ];
+        for (src, as_i64, as_f64) in valid {
+            for src in [src.to_string(), format!("[{src}]")] {
+                let value = parse_str(&src).unwrap();
+                let number = match &value {
+                    JsonValue::Number(number) => number,
+                    value => match value.get("0") {
+                        Some(JsonValue::Number(number)) => number,
+                        _ => panic!("expected number: {src}"),
+                    },
+                };
+                assert_eq!(number.as_i64(), *as_i64, "{src}");
+                assert_eq!(number.as_f64(), Some(*as_f64), "{src}");
+            }
+        }
+
+        // Forms accepted by some readers, but forbidden by JSON.
+        let invalid = [
+            "01",
+            "-01",
+            "00",
+            "+1",
+            ".5",
+            "-.5",
+            "1.",
+            "1.e3",
+            "-",
+            "1e",
+            "1e+",
+            "0x1",
+            "NaN",
+            "Infinity",
+            "-Infinity",
+            "1_000",
+        ];
+        for src in invalid {
+            assert!(parse_str(src).is_err(), "{src}");
+            assert!(parse_str(&format!("[{src}]")).is_err(), "{src}");
+        }
+    }
+
+    #[test]
+    fn test_parse_stream() {
+        let src = Bytes::from_static(b" \"a\"\"\"{\"b\": [1]}\n\t2 3 null[]");
+
+        let values = parse_stream(src.clone())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let spans = values
+            .iter()
+            .map(|value| value.span().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec!["a", "", "{\"b\": [1]}", "2", "3", "null", "[]"]);
+        assert_eq!(values[2].get("b.0").unwrap().span().indices(), &(13..14));
+
+        assert_eq!(parse_stream(Bytes::from_static(b"  \n")).count(), 0);
+
+        let mut values = parse_stream(Bytes::from_static(b"[1] {\"a\": 1} {\"b\":"));
+        assert!(values.next().unwrap().is_ok());
+        assert!(values.next().unwrap().is_ok());
+        assert!(values.next().unwrap().is_err());
+        assert!(values.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_stream_bad_tail() {
+        // Invalid UTF-8 after the values.
+        let mut values = parse_stream(Bytes::from_static(b"[1] [2] \xff"));
+        assert_eq!(values.next().unwrap().unwrap().span(), "[1]");
+        assert_eq!(values.next().unwrap().unwrap().span(), "[2]");
+        assert_eq!(
+            values.next().unwrap().err().unwrap().to_string(),
+            "parsing error: invalid utf-8 sequence at index 8"
+        );
+        assert!(values.next().is_none());
+
+        // Invalid UTF-8 within a value.
+        let mut values = parse_stream(Bytes::from_static(b"[1] [\"a\xff\"]"));
+        assert!(values.next().unwrap().is_ok());
+        assert!(values.next().unwrap().is_err());
+
+        // Excessive nesting after the values.
+        let src = format!("[1] {{\"a\": 2}} {}", "[".repeat(200));
+        let mut values = parse_stream(Bytes::from(src));
+        assert!(values.next().unwrap().is_ok());
+        assert!(values.next().unwrap().is_ok());
+        assert_eq!(
+            values.next().unwrap().err().unwrap().to_string(),
+            "parsing error: maximum nesting depth of 128 exceeded"
+        );
+    }
+
     #[test]
     fn test_err_leading_characters() {
         let src = " {\"foo\": \"bar\"}";
@@ -1,8 +1,11 @@
 use std::ops::{Index, Range};
 
+use bytes::Bytes;
 use utils::range::{RangeDifference, RangeSet};
 
-use crate::{Span, Spanned};
+use crate::{ParseError, Source, Span, Spanned};
+
+use super::JsonVisit;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -23,6 +26,38 @@ pub enum JsonValue {
 }
 
 impl JsonValue {
+    /// Returns the source bytes the value was parsed from.
+    ///
+    /// This is a cheap clone of the buffer which all spans of the value are sliced from,
+    /// so the indices of the spans index directly into it, unless the value was
+    /// [offset](Self::offset). A deserialized value has an empty source.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use spansy::{json::parse_from_bytes, Spanned};
+    ///
+    /// let src = Bytes::from_static(b"body: {\"foo\": [1, 2]}");
+    /// let value = parse_from_bytes(&src, 6).unwrap();
+    ///
+    /// let source = value.source();
+    /// let idx = value.get("foo.1").unwrap().span().indices().min().unwrap();
+    ///
+    /// assert_eq!(source, src);
+    /// assert_eq!(source[idx], b'2');
+    /// ```
+    pub fn source(&self) -> Bytes {
+        match self {
+            JsonValue::Null(v) => v.1 .0.clone(),
+            JsonValue::Bool(v) => v.1 .0.clone(),
+            JsonValue::Number(v) => v.1 .0.clone(),
+            JsonValue::String(v) => v.1 .0.clone(),
+            JsonValue::Array(v) => v.source.0.clone(),
+            JsonValue::Object(v) => v.source.0.clone(),
+        }
+    }
+
     /// Returns the span corresponding to the value.
     pub fn into_span(self) -> Span<str> {
         match self {
@@ -35,6 +70,45 @@ impl JsonValue {
         }
     }
 
+    /// Returns a copy of the value with spans rebuilt from the given source bytes.
+    ///
+    /// `src` must contain the source data starting at `origin`.
+    pub(crate) fn compact(&self, src: &Bytes, origin: usize) -> Self {
+        match self {
+            JsonValue::Null(v) => {
+                JsonValue::Null(Null(v.0.compact(src, origin), Source(src.clone())))
+            }
+            JsonValue::Bool(v) => {
+                JsonValue::Bool(Bool(v.0.compact(src, origin), Source(src.clone())))
+            }
+            JsonValue::Number(v) => {
+                JsonValue::Number(Number(v.0.compact(src, origin), Source(src.clone())))
+            }
+            JsonValue::String(v) => {
+                JsonValue::String(String(v.0.compact(src, origin), Source(src.clone())))
+            }
+            JsonValue::Array(v) => JsonValue::Array(Array {
+                span: v.span.compact(src, origin),
+                source: Source(src.clone()),
+                elems: v.elems.iter().map(|v| v.compact(src, origin)).collect(),
+            }),
+            JsonValue::Object(v) => JsonValue::Object(Object {
+                span: v.span.compact(src, origin),
+                source: Source(src.clone()),
+                elems: v
+                    .elems
+                    .iter()
+                    .map(|kv| KeyValue {
+                        span: kv.span.compact(src, origin),
+                        span_with_trailing: kv.span_with_trailing.compact(src, origin),
+                        key: JsonKey(kv.key.0.compact(src, origin)),
+                        value: kv.value.compact(src, origin),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+
     /// Shifts the span range by the given offset.
     pub fn offset(&mut self, offset: usize) {
         match self {
@@ -50,6 +124,7 @@ impl JsonValue {
                 v.span.offset(offset);
                 v.elems.iter_mut().for_each(|kv| {
                     kv.span.offset(offset);
+                    kv.span_with_trailing.offset(offset);
                     kv.key.offset(offset);
                     kv.value.offset(offset);
                 })
@@ -96,6 +171,415 @@ impl JsonValue {
             JsonValue::Object(v) => v.get(path),
         }
     }
+
+    /// Returns the spans of every value in the document, in source order.
+    ///
+    /// This includes the spans of arrays and objects, each preceding the spans of its
+    /// elements, but excludes object keys. See [`JsonValue::leaf_spans`] for only the
+    /// spans of scalar values.
+    pub fn spans(&self) -> Vec<Span<str>> {
+        let mut collector = SpanCollector {
+            containers: true,
+            spans: Vec::new(),
+        };
+        collector.visit_value(self);
+        collector.spans
+    }
+
+    /// Returns the spans of every scalar value in the document, in source order.
+    ///
+    /// Scalar values are strings, numbers, booleans and nulls.
+    pub fn leaf_spans(&self) -> Vec<Span<str>> {
+        let mut collector = SpanCollector {
+            containers: false,
+            spans: Vec::new(),
+        };
+        collector.visit_value(self);
+        collector.spans
+    }
+
+    /// Returns the indices of the value, excluding the values at the given paths.
+    ///
+    /// Paths which do not resolve to a value are ignored, see [`JsonValue::try_without`]
+    /// for a strict alternative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::json::parse_str;
+    /// use utils::range::IndexRanges;
+    ///
+    /// let src = "{\"foo\": \"secret\", \"bar\": [42, 14]}";
+    ///
+    /// let value = parse_str(src).unwrap();
+    ///
+    /// let indices = value.without(&["foo", "bar.1", "baz"]);
+    ///
+    /// assert_eq!(src.index_ranges(&indices), "{\"foo\": \"\", \"bar\": [42, ]}");
+    /// ```
+    pub fn without(&self, paths: &[&str]) -> RangeSet<usize> {
+        let mut indices = self.span().indices.clone();
+        for value in paths.iter().filter_map(|path| self.get(path)) {
+            indices = indices.difference(&value.span().indices);
+        }
+        indices
+    }
+
+    /// Returns the indices of the value, excluding the values at the given paths.
+    ///
+    /// Returns `None` if any of the paths do not resolve to a value.
+    pub fn try_without(&self, paths: &[&str]) -> Option<RangeSet<usize>> {
+        let mut indices = self.span().indices.clone();
+        for path in paths {
+            indices = indices.difference(&self.get(path)?.span().indices);
+        }
+        Some(indices)
+    }
+
+    /// Returns the path to the most specific value containing the given index.
+    ///
+    /// The path of the value itself is the empty string. Returns `None` if the index is not
+    /// within the span of the value. Keys are not escaped, see [`PathSegment::join`](super::PathSegment::join).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::json::parse_str;
+    ///
+    /// let src = "{\"a\":{\"b\":[1,2]}}";
+    ///
+    /// let value = parse_str(src).unwrap();
+    ///
+    /// assert_eq!(value.path_at(13).unwrap(), "a.b.1");
+    /// assert_eq!(value.path_at(2).unwrap(), "");
+    /// ```
+    pub fn path_at(&self, index: usize) -> Option<std::string::String> {
+        if !self.span().contains(index) {
+            return None;
+        }
+
+        let mut segments = Vec::new();
+        let mut value = self;
+        loop {
+            let child = match value {
+                JsonValue::Array(v) => v
+                    .elems
+                    .iter()
+                    .enumerate()
+                    .find(|(_, elem)| elem.span().contains(index))
+                    .map(|(idx, elem)| (idx.to_string(), elem)),
+                JsonValue::Object(v) => v
+                    .elems
+                    .iter()
+                    .find(|kv| kv.value.span().contains(index))
+                    .map(|kv| (kv.key.0.as_str().to_string(), &kv.value)),
+                _ => None,
+            };
+
+            match child {
+                Some((segment, child)) => {
+                    segments.push(segment);
+                    value = child;
+                }
+                None => break,
+            }
+        }
+
+        Some(segments.join("."))
+    }
+
+    /// Returns the value rendered as indented JSON, along with the range of each value in the
+    /// output and its path.
+    ///
+    /// Values are listed in pre-order, starting with the value itself at the empty path.
+    /// Strings and numbers are emitted exactly as they appear in the source.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::json::parse_str;
+    ///
+    /// let value = parse_str("{\"a\":[1,2]}").unwrap();
+    ///
+    /// let (pretty, map) = value.to_pretty_string();
+    ///
+    /// assert_eq!(pretty, "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+    /// assert_eq!(map[3], (22..23, "a.1".to_string()));
+    /// ```
+    pub fn to_pretty_string(
+        &self,
+    ) -> (
+        std::string::String,
+        Vec<(Range<usize>, std::string::String)>,
+    ) {
+        let mut printer = PrettyPrinter::default();
+        printer.visit_value(self);
+
+        (printer.out, printer.map)
+    }
+
+    /// Returns the path of every leaf value, in document order.
+    ///
+    /// Leaves are values other than arrays and objects, as well as empty arrays and
+    /// objects. Array indices are numeric path segments, and object keys are as they
+    /// appear in the source, so each path can be passed to [`JsonValue::get`] unless it
+    /// contains a key with a `.`, see [`PathSegment::join`](super::PathSegment::join). If the value itself is a
+    /// leaf, its path is the empty string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::json::parse_str;
+    ///
+    /// let value = parse_str("{\"a\":{\"b\":1},\"c\":[2]}").unwrap();
+    ///
+    /// assert_eq!(value.paths(), vec!["a.b", "c.0"]);
+    /// ```
+    pub fn paths(&self) -> Vec<std::string::String> {
+        let mut collector = PathCollector::default();
+        collector.visit_value(self);
+
+        collector.paths
+    }
+
+    /// Returns `true` if the value is an object.
+    pub fn is_object(&self) -> bool {
+        matches!(self, JsonValue::Object(_))
+    }
+
+    /// Returns `true` if the value is an array.
+    pub fn is_array(&self) -> bool {
+        matches!(self, JsonValue::Array(_))
+    }
+
+    /// Returns `true` if the value is a string.
+    pub fn is_string(&self) -> bool {
+        matches!(self, JsonValue::String(_))
+    }
+
+    /// Returns `true` if the value is a number.
+    pub fn is_number(&self) -> bool {
+        matches!(self, JsonValue::Number(_))
+    }
+
+    /// Returns `true` if the value is a boolean.
+    pub fn is_bool(&self) -> bool {
+        matches!(self, JsonValue::Bool(_))
+    }
+
+    /// Returns `true` if the value is null.
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null(_))
+    }
+
+    /// Returns a reference to the object if the value is an object.
+    pub fn as_object(&self) -> Option<&Object> {
+        match self {
+            JsonValue::Object(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the array if the value is an array.
+    pub fn as_array(&self) -> Option<&Array> {
+        match self {
+            JsonValue::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the string slice if the value is a string.
+    ///
+    /// The returned slice is the raw source text between the quotes, escape
+    /// sequences are not decoded.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(v) => Some(v.0.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean if the value is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(v) => v.0.as_str().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the number if the value is a number.
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            JsonValue::Number(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Collects the spans of visited values.
+struct SpanCollector {
+    /// Whether to include the spans of arrays and objects.
+    containers: bool,
+    spans: Vec<Span<str>>,
+}
+
+impl JsonVisit for SpanCollector {
+    fn visit_array(&mut self, node: &Array) {
+        if self.containers {
+            self.spans.push(node.span.clone());
+        }
+
+        for elem in &node.elems {
+            self.visit_value(elem);
+        }
+    }
+
+    fn visit_object(&mut self, node: &Object) {
+        if self.containers {
+            self.spans.push(node.span.clone());
+        }
+
+        for kv in &node.elems {
+            self.visit_key_value(kv);
+        }
+    }
+
+    fn visit_null(&mut self, node: &Null) {
+        self.spans.push(node.0.clone());
+    }
+
+    fn visit_bool(&mut self, node: &Bool) {
+        self.spans.push(node.0.clone());
+    }
+
+    fn visit_number(&mut self, node: &Number) {
+        self.spans.push(node.0.clone());
+    }
+
+    fn visit_string(&mut self, node: &String) {
+        self.spans.push(node.0.clone());
+    }
+}
+
+/// Collects the paths of leaf values, see [`JsonValue::paths`].
+#[derive(Default)]
+struct PathCollector {
+    /// The path segments of the current value.
+    path: Vec<std::string::String>,
+    paths: Vec<std::string::String>,
+}
+
+impl JsonVisit for PathCollector {
+    fn visit_key_value(&mut self, node: &KeyValue) {
+        self.path.push(node.key.0.as_str().to_string());
+        self.visit_value(&node.value);
+        self.path.pop();
+    }
+
+    fn visit_value(&mut self, node: &JsonValue) {
+        match node {
+            JsonValue::Array(v) if !v.is_empty() => self.visit_array(v),
+            JsonValue::Object(v) if !v.is_empty() => self.visit_object(v),
+            _ => self.paths.push(self.path.join(".")),
+        }
+    }
+
+    fn visit_array(&mut self, node: &Array) {
+        for (idx, elem) in node.elems.iter().enumerate() {
+            self.path.push(idx.to_string());
+            self.visit_value(elem);
+            self.path.pop();
+        }
+    }
+}
+
+/// Renders visited values as indented JSON.
+#[derive(Default)]
+struct PrettyPrinter {
+    out: std::string::String,
+    /// The path segments of the current value.
+    path: Vec<std::string::String>,
+    /// The output range and path of each value.
+    map: Vec<(Range<usize>, std::string::String)>,
+}
+
+impl PrettyPrinter {
+    /// Writes a newline followed by the indentation of the current depth.
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.path.len() {
+            self.out.push_str("  ");
+        }
+    }
+
+    /// Writes a sequence of elements enclosed by the given delimiters.
+    fn write_seq<T>(
+        &mut self,
+        open: char,
+        close: char,
+        elems: &[T],
+        mut f: impl FnMut(&mut Self, usize, &T),
+    ) {
+        self.out.push(open);
+        if elems.is_empty() {
+            self.out.push(close);
+            return;
+        }
+
+        for (idx, elem) in elems.iter().enumerate() {
+            if idx > 0 {
+                self.out.push(',');
+            }
+            self.path.push(std::string::String::new());
+            self.newline();
+            f(self, idx, elem);
+            self.path.pop();
+        }
+
+        self.newline();
+        self.out.push(close);
+    }
+}
+
+impl JsonVisit for PrettyPrinter {
+    fn visit_value(&mut self, node: &JsonValue) {
+        let start = self.out.len();
+        let pos = self.map.len();
+        self.map.push((start..start, self.path.join(".")));
+
+        match node {
+            JsonValue::Array(v) => self.visit_array(v),
+            JsonValue::Object(v) => self.visit_object(v),
+            JsonValue::String(v) => self.visit_string(v),
+            _ => self.out.push_str(node.span().as_str()),
+        }
+
+        self.map[pos].0.end = self.out.len();
+    }
+
+    fn visit_array(&mut self, node: &Array) {
+        self.write_seq('[', ']', &node.elems, |this, idx, elem| {
+            *this.path.last_mut().expect("path is not empty") = idx.to_string();
+            this.visit_value(elem);
+        });
+    }
+
+    fn visit_object(&mut self, node: &Object) {
+        self.write_seq('{', '}', &node.elems, |this, _, kv| {
+            let key = kv.key.0.as_str();
+            *this.path.last_mut().expect("path is not empty") = key.to_string();
+            this.out.push('"');
+            this.out.push_str(key);
+            this.out.push_str("\": ");
+            this.visit_value(&kv.value);
+        });
+    }
+
+    fn visit_string(&mut self, node: &String) {
+        self.out.push('"');
+        self.out.push_str(node.0.as_str());
+        self.out.push('"');
+    }
 }
 
 /// A key value pair in a JSON object.
@@ -103,6 +587,7 @@ impl JsonValue {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyValue {
     pub(crate) span: Span<str>,
+    pub(crate) span_with_trailing: Span<str>,
 
     /// The key of the pair.
     pub key: JsonKey,
@@ -115,6 +600,53 @@ impl KeyValue {
     pub fn without_value(&self) -> RangeSet<usize> {
         self.span.indices.difference(&self.value.span().indices)
     }
+
+    /// Returns the span of the key value pair including the whitespace following it, up
+    /// to the next pair or the closing brace of the object.
+    ///
+    /// The span of a pair includes its trailing comma, if any, so this can be used to
+    /// remove a pair along with its separating comma and whitespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::{json::parse_str, Spanned};
+    ///
+    /// let value = parse_str("{\"a\": 1 ,  \"b\": 2 }").unwrap();
+    /// let obj = value.as_object().unwrap();
+    ///
+    /// assert_eq!(obj.elems[0].span(), "\"a\": 1 ,");
+    /// assert_eq!(obj.elems[0].span_with_trailing(), "\"a\": 1 ,  ");
+    /// assert_eq!(obj.elems[1].span_with_trailing(), "\"b\": 2 ");
+    /// ```
+    pub fn span_with_trailing(&self) -> Span<str> {
+        self.span_with_trailing.clone()
+    }
+
+    /// Returns the span of the separator between the key and the value, ie. the `:`
+    /// and any surrounding whitespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::json::parse_str;
+    ///
+    /// let value = parse_str("{\"foo\" : 1}").unwrap();
+    /// let kv = value.as_object().unwrap().get_key_value("foo").unwrap();
+    ///
+    /// assert_eq!(kv.separator(), " : ");
+    /// assert_eq!(kv.separator(), 6..9);
+    /// ```
+    pub fn separator(&self) -> Span<str> {
+        // The pair starts with the quoted key, and the value can not start with
+        // whitespace or a colon.
+        let start = self.key.0.len() + 2;
+        let len = self.span.as_str()[start..]
+            .find(|c: char| !matches!(c, ':' | ' ' | '\t' | '\r' | '\n'))
+            .expect("pair contains a value");
+
+        self.span.subspan(start..start + len)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -122,31 +654,89 @@ impl KeyValue {
 /// A key in a JSON object.
 pub struct JsonKey(pub(crate) Span<str>);
 
+impl JsonKey {
+    /// Returns the key with all escape sequences decoded.
+    pub fn unescape(&self) -> Result<std::string::String, ParseError> {
+        unescape(self.0.as_str())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 /// A null value.
-pub struct Null(pub(crate) Span<str>);
+pub struct Null(
+    pub(crate) Span<str>,
+    #[cfg_attr(feature = "serde", serde(skip))] pub(crate) Source,
+);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 /// A boolean value.
-pub struct Bool(pub(crate) Span<str>);
+pub struct Bool(
+    pub(crate) Span<str>,
+    #[cfg_attr(feature = "serde", serde(skip))] pub(crate) Source,
+);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 /// A number value.
-pub struct Number(pub(crate) Span<str>);
+pub struct Number(
+    pub(crate) Span<str>,
+    #[cfg_attr(feature = "serde", serde(skip))] pub(crate) Source,
+);
+
+impl Number {
+    /// Returns the number as a `u64` if it is an integer which fits.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.0.as_str().parse().ok()
+    }
+
+    /// Returns the number as an `i64` if it is an integer which fits.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.0.as_str().parse().ok()
+    }
+
+    /// Returns the number as an `f64`.
+    ///
+    /// Returns `None` if the number is not finite when represented as an `f64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        self.0
+            .as_str()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 /// A string value.
-pub struct String(pub(crate) Span<str>);
+pub struct String(
+    pub(crate) Span<str>,
+    #[cfg_attr(feature = "serde", serde(skip))] pub(crate) Source,
+);
+
+impl String {
+    /// Returns the string value with all escape sequences decoded.
+    ///
+    /// The span of a string excludes the surrounding quotes but includes any escape
+    /// sequences as they appear in the source.
+    pub fn unescape(&self) -> Result<std::string::String, ParseError> {
+        unescape(self.0.as_str())
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// An array value.
 pub struct Array {
     pub(crate) span: Span<str>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) source: Source,
     /// The elements of the array.
     pub elems: Vec<JsonValue>,
 }
@@ -168,6 +758,21 @@ impl Array {
         }
     }
 
+    /// Returns the number of values in the array.
+    pub fn len(&self) -> usize {
+        self.elems.len()
+    }
+
+    /// Returns `true` if the array has no values.
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+
+    /// Returns an iterator over the values of the array, in source order.
+    pub fn iter(&self) -> impl Iterator<Item = &JsonValue> {
+        self.elems.iter()
+    }
+
     /// Returns the indices of the array, excluding the values and separators.
     pub fn without_values(&self) -> RangeSet<usize> {
         let start = self
@@ -183,6 +788,108 @@ impl Array {
 
         RangeSet::from([start..start + 1, end..end + 1])
     }
+
+    /// Returns the indices to remove to delete the value at the given index, such that
+    /// the remaining array is still valid JSON.
+    ///
+    /// This includes the value and one adjacent comma, along with the whitespace between
+    /// them. The first value is removed with its trailing comma, and any other value with
+    /// its leading comma. If the array has a single value, the indices cover everything
+    /// between the brackets.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::json::parse_str;
+    ///
+    /// let src = "[1, 2, 3]";
+    /// let value = parse_str(src).unwrap();
+    /// let array = value.as_array().unwrap();
+    ///
+    /// let indices = array.deletion_range(1).unwrap();
+    /// let remaining = src
+    ///     .char_indices()
+    ///     .filter(|(idx, _)| !indices.contains(idx))
+    ///     .map(|(_, c)| c)
+    ///     .collect::<String>();
+    ///
+    /// assert_eq!(remaining, "[1, 3]");
+    /// ```
+    pub fn deletion_range(&self, index: usize) -> Option<RangeSet<usize>> {
+        if index >= self.elems.len() {
+            return None;
+        }
+
+        let ranges = self.value_ranges();
+        let range = if ranges.len() == 1 {
+            let start = self
+                .span
+                .indices
+                .min()
+                .expect("array has at least brackets");
+            let end = self
+                .span
+                .indices
+                .max()
+                .expect("array has at least brackets");
+            start + 1..end
+        } else if index == 0 {
+            ranges[0].start..ranges[1].start
+        } else {
+            ranges[index - 1].end..ranges[index].end
+        };
+
+        Some(RangeSet::from(range))
+    }
+
+    /// Returns the ranges of the values within the source, including the quotes of
+    /// strings.
+    fn value_ranges(&self) -> Vec<Range<usize>> {
+        let start = self
+            .span
+            .indices
+            .min()
+            .expect("array has at least brackets");
+        let src = self.span.as_str().as_bytes();
+
+        // The span of an empty string is empty, so its position is found by searching
+        // for its quotes after the preceding value.
+        let mut pos = start + 1;
+        self.elems
+            .iter()
+            .map(|elem| {
+                let range = match (elem, elem.span().indices.min()) {
+                    (JsonValue::String(_), None) => {
+                        let quote = src[pos - start..]
+                            .iter()
+                            .position(|b| *b == b'"')
+                            .expect("empty string is quoted");
+                        pos + quote..pos + quote + 2
+                    }
+                    (JsonValue::String(value), Some(min)) => min - 1..min + value.0.len() + 1,
+                    (_, Some(min)) => min..min + elem.span().len(),
+                    (_, None) => unreachable!("only empty strings have empty spans"),
+                };
+                pos = range.end;
+                range
+            })
+            .collect()
+    }
+
+    /// Returns the indices of the array, excluding the values.
+    ///
+    /// This includes the brackets and all bytes between values, ie. the separators and any
+    /// whitespace. Like the spans of string values, this excludes their quotes, so the
+    /// quotes are included here.
+    pub fn structural(&self) -> RangeSet<usize> {
+        let mut indices = self.span.indices.clone();
+        for elem in &self.elems {
+            indices = indices.difference(&elem.span().indices);
+        }
+        indices
+    }
 }
 
 impl Index<usize> for Array {
@@ -203,6 +910,8 @@ impl Index<usize> for Array {
 /// A JSON object value.
 pub struct Object {
     pub(crate) span: Span<str>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) source: Source,
     /// The key value pairs of the object.
     pub elems: Vec<KeyValue>,
 }
@@ -214,7 +923,7 @@ impl Object {
 
         let key = path_iter.next()?;
 
-        let KeyValue { value, .. } = self.elems.iter().find(|kv| kv.key == key)?;
+        let KeyValue { value, .. } = self.get_key_value(key)?;
 
         if path_iter.next().is_some() {
             value.get(&path[key.len() + 1..])
@@ -223,6 +932,113 @@ impl Object {
         }
     }
 
+    /// Returns the number of key value pairs in the object.
+    pub fn len(&self) -> usize {
+        self.elems.len()
+    }
+
+    /// Returns `true` if the object has no key value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+
+    /// Returns an iterator over the keys and values of the object, in source order.
+    pub fn entries(&self) -> impl Iterator<Item = (&JsonKey, &JsonValue)> {
+        self.elems.iter().map(|kv| (&kv.key, &kv.value))
+    }
+
+    /// Returns an iterator over the keys of the object, in source order.
+    pub fn keys(&self) -> impl Iterator<Item = &JsonKey> {
+        self.elems.iter().map(|kv| &kv.key)
+    }
+
+    /// Returns an iterator over the values of the object, in source order.
+    pub fn values(&self) -> impl Iterator<Item = &JsonValue> {
+        self.elems.iter().map(|kv| &kv.value)
+    }
+
+    /// Get a reference to the key value pair with the given key.
+    ///
+    /// Unlike [`Object::get`], the key is not interpreted as a path.
+    pub fn get_key_value(&self, key: &str) -> Option<&KeyValue> {
+        self.elems.iter().find(|kv| kv.key == key)
+    }
+
+    /// Returns the indices to remove to delete the first pair with the given key, such
+    /// that the remaining object is still valid JSON.
+    ///
+    /// This includes the pair and one adjacent comma, along with the whitespace between
+    /// them. The last pair is removed with its leading comma, and any other pair with its
+    /// trailing comma. If the object has a single pair, the indices cover everything
+    /// between the braces. Like [`Object::get_key_value`], the key is not interpreted as
+    /// a path.
+    ///
+    /// Returns `None` if the object does not contain the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::json::parse_str;
+    ///
+    /// let src = "{\"a\": 1, \"b\": 2, \"c\": 3}";
+    /// let value = parse_str(src).unwrap();
+    /// let object = value.as_object().unwrap();
+    ///
+    /// let indices = object.deletion_range("b").unwrap();
+    /// let remaining = src
+    ///     .char_indices()
+    ///     .filter(|(idx, _)| !indices.contains(idx))
+    ///     .map(|(_, c)| c)
+    ///     .collect::<String>();
+    ///
+    /// assert_eq!(remaining, "{\"a\": 1, \"c\": 3}");
+    /// ```
+    pub fn deletion_range(&self, key: &str) -> Option<RangeSet<usize>> {
+        let index = self.elems.iter().position(|kv| kv.key == key)?;
+        let kv = &self.elems[index];
+
+        if self.elems.len() == 1 {
+            let start = self.span.indices.min().expect("object has at least braces");
+            let end = self.span.indices.max().expect("object has at least braces");
+            return Some(RangeSet::from(start + 1..end));
+        }
+
+        if index + 1 < self.elems.len() {
+            // The pair includes its trailing comma.
+            return Some(kv.span_with_trailing.indices.clone());
+        }
+
+        // The span of the preceding pair includes its trailing comma, if any. Commas
+        // are optional in the lenient grammar, in which case the pair is removed with
+        // the whitespace preceding it.
+        let origin = self.span.indices.min().expect("object has at least braces");
+        let prev_end = self.elems[index - 1]
+            .span
+            .indices
+            .max()
+            .expect("pair is not empty");
+        let start = if self.span.as_str().as_bytes()[prev_end - origin] == b',' {
+            prev_end
+        } else {
+            prev_end + 1
+        };
+        let end = kv.span.indices.max().expect("pair is not empty");
+
+        Some(RangeSet::from(start..end + 1))
+    }
+
+    /// Get a reference to the value of the first key which matches the given key,
+    /// ignoring ASCII case.
+    ///
+    /// Like [`Object::get_key_value`], the key is not interpreted as a path. Keys are
+    /// compared as they appear in the source, without unescaping.
+    pub fn get_ignore_case(&self, key: &str) -> Option<&JsonValue> {
+        self.elems
+            .iter()
+            .find(|kv| kv.key.0.as_str().eq_ignore_ascii_case(key))
+            .map(|kv| &kv.value)
+    }
+
     /// Returns the indices of the object, excluding the key value pairs.
     pub fn without_pairs(&self) -> RangeSet<usize> {
         let mut indices = self.span.indices.clone();
@@ -246,6 +1062,106 @@ impl Index<&str> for Object {
     }
 }
 
+#[cfg(feature = "serde_json")]
+impl TryFrom<&JsonValue> for serde_json::Value {
+    type Error = ParseError;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            JsonValue::Null(_) => serde_json::Value::Null,
+            JsonValue::Bool(v) => serde_json::Value::Bool(v.0.as_str() == "true"),
+            JsonValue::Number(v) => {
+                let number = if let Some(n) = v.as_u64() {
+                    serde_json::Number::from(n)
+                } else if let Some(n) = v.as_i64() {
+                    serde_json::Number::from(n)
+                } else {
+                    v.as_f64()
+                        .and_then(serde_json::Number::from_f64)
+                        .ok_or_else(|| ParseError(format!("invalid number: {}", v.0.as_str())))?
+                };
+
+                serde_json::Value::Number(number)
+            }
+            JsonValue::String(v) => serde_json::Value::String(v.unescape()?),
+            JsonValue::Array(v) => serde_json::Value::Array(
+                v.elems
+                    .iter()
+                    .map(serde_json::Value::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            JsonValue::Object(v) => serde_json::Value::Object(
+                v.elems
+                    .iter()
+                    .map(|kv| Ok((kv.key.unescape()?, (&kv.value).try_into()?)))
+                    .collect::<Result<_, ParseError>>()?,
+            ),
+        })
+    }
+}
+
+/// Decodes the escape sequences of a JSON string.
+pub(crate) fn unescape(src: &str) -> Result<std::string::String, ParseError> {
+    let mut out = std::string::String::with_capacity(src.len());
+    let mut chars = src.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some('/') => out.push('/'),
+            Some('b') => out.push('\u{8}'),
+            Some('f') => out.push('\u{c}'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('u') => {
+                let high = unescape_code_unit(&mut chars)?;
+
+                let code = if (0xD800..0xDC00).contains(&high) {
+                    // A high surrogate must be followed by an escaped low surrogate.
+                    if chars.next() != Some('\\') || chars.next() != Some('u') {
+                        return Err(ParseError(format!("unpaired surrogate in string: {src}")));
+                    }
+
+                    let low = unescape_code_unit(&mut chars)?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(ParseError(format!("unpaired surrogate in string: {src}")));
+                    }
+
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    high
+                };
+
+                out.push(char::from_u32(code).ok_or_else(|| {
+                    ParseError(format!("invalid unicode escape in string: {src}"))
+                })?);
+            }
+            _ => return Err(ParseError(format!("invalid escape in string: {src}"))),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decodes the 4 hex digits of a `\uXXXX` escape sequence.
+fn unescape_code_unit(chars: &mut std::str::Chars<'_>) -> Result<u32, ParseError> {
+    let digits: std::string::String = chars.take(4).collect();
+
+    if digits.len() != 4 {
+        return Err(ParseError(format!("invalid unicode escape: {digits}")));
+    }
+
+    u32::from_str_radix(&digits, 16)
+        .map_err(|_| ParseError(format!("invalid unicode escape: {digits}")))
+}
+
 macro_rules! impl_type {
     ($ty:ident, $span:tt) => {
         impl $ty {
@@ -329,7 +1245,7 @@ impl_type!(KeyValue, span);
 mod tests {
     use utils::range::IndexRanges;
 
-    use crate::json::parse_str;
+    use crate::json::{parse_str, parse_str_jsonc};
 
     use super::*;
 
@@ -360,6 +1276,228 @@ mod tests {
         assert_eq!(value.get("foo.bar.1").unwrap().span(), "14");
     }
 
+    #[test]
+    fn test_value_accessors() {
+        let src = "{\"a\": {}, \"b\": [], \"c\": \"d\", \"e\": 1, \"f\": false, \"g\": null}";
+
+        let value = parse_str(src).unwrap();
+
+        assert!(value.is_object());
+        assert!(value.get("a").unwrap().as_object().is_some());
+        assert!(value.get("b").unwrap().is_array());
+        assert!(value.get("b").unwrap().as_array().is_some());
+        assert!(value.get("c").unwrap().is_string());
+        assert_eq!(value.get("c").unwrap().as_str(), Some("d"));
+        assert!(value.get("e").unwrap().is_number());
+        assert_eq!(value.get("e").unwrap().as_number().unwrap(), "1");
+        assert!(value.get("f").unwrap().is_bool());
+        assert_eq!(value.get("f").unwrap().as_bool(), Some(false));
+        assert!(value.get("g").unwrap().is_null());
+
+        assert!(value.get("g").unwrap().as_bool().is_none());
+        assert!(value.get("c").unwrap().as_object().is_none());
+    }
+
+    #[test]
+    fn test_obj_get_key_value() {
+        let src = "{\"foo\": {\"bar\": 42}}";
+
+        let JsonValue::Object(value) = parse_str(src).unwrap() else {
+            panic!("expected object");
+        };
+
+        let kv = value.get_key_value("foo").unwrap();
+
+        assert_eq!(kv.key.span().indices(), 2..5);
+        assert_eq!(kv.value.get("bar").unwrap().span(), "42");
+        assert!(value.get_key_value("foo.bar").is_none());
+    }
+
+    #[test]
+    fn test_obj_entries() {
+        let value = parse_str("{\"b\": 1, \"a\": [2, 3], \"c\": null}").unwrap();
+        let obj = value.as_object().unwrap();
+
+        let keys = obj.keys().map(|key| key.0.as_str()).collect::<Vec<_>>();
+        assert_eq!(keys, ["b", "a", "c"]);
+
+        let values = obj.values().map(|v| v.span().as_str()).collect::<Vec<_>>();
+        assert_eq!(values, ["1", "[2, 3]", "null"]);
+
+        let entries = obj.entries().collect::<Vec<_>>();
+        assert_eq!(entries[1], (&obj.elems[1].key, &obj.elems[1].value));
+
+        let arr = obj.get("a").unwrap().as_array().unwrap();
+        let values = arr.iter().map(|v| v.span().as_str()).collect::<Vec<_>>();
+        assert_eq!(values, ["2", "3"]);
+    }
+
+    #[test]
+    fn test_container_len() {
+        let value = parse_str("{\"a\": [1, 2, 3], \"b\": {}, \"c\": [ ]}").unwrap();
+
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj.len(), 3);
+        assert!(!obj.is_empty());
+
+        let arr = value.get("a").unwrap().as_array().unwrap();
+        assert_eq!(arr.len(), 3);
+        assert!(!arr.is_empty());
+
+        let empty = value.get("b").unwrap().as_object().unwrap();
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+
+        let empty = value.get("c").unwrap().as_array().unwrap();
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_obj_get_ignore_case() {
+        let value = parse_str("{\"Foo\": 1, \"foo\": 2, \"BAR\": 3}").unwrap();
+        let obj = value.as_object().unwrap();
+
+        assert_eq!(obj.get_ignore_case("FOO").unwrap().span(), "1");
+        assert_eq!(obj.get_ignore_case("bar").unwrap().span(), "3");
+        assert!(obj.get_ignore_case("baz").is_none());
+
+        assert_eq!(obj.get("foo").unwrap().span(), "2");
+        assert!(obj.get("bar").is_none());
+    }
+
+    #[test]
+    fn test_string_unescape() {
+        let src = r#"["foo\"bar", "\\\/\b\f\n\r\t", "\u00e9\ud83d\ude00"]"#;
+
+        let value = parse_str(src).unwrap();
+
+        let unescaped = value
+            .as_array()
+            .unwrap()
+            .elems
+            .iter()
+            .map(|v| match v {
+                JsonValue::String(s) => s.unescape().unwrap(),
+                _ => panic!("expected string"),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            unescaped,
+            ["foo\"bar", "\\/\u{8}\u{c}\n\r\t", "\u{e9}\u{1f600}"]
+        );
+    }
+
+    #[test]
+    fn test_number_accessors() {
+        let src = "[42, -1, 1.5e3, 18446744073709551616]";
+
+        let value = parse_str(src).unwrap();
+        let numbers = value
+            .as_array()
+            .unwrap()
+            .elems
+            .iter()
+            .map(|v| v.as_number().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(numbers[0].as_u64(), Some(42));
+        assert_eq!(numbers[1].as_u64(), None);
+        assert_eq!(numbers[1].as_i64(), Some(-1));
+        assert_eq!(numbers[2].as_i64(), None);
+        assert_eq!(numbers[2].as_f64(), Some(1500.0));
+        assert_eq!(numbers[3].as_u64(), None);
+        assert_eq!(numbers[3].as_f64(), Some(18446744073709551616.0));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_serde_json_value() {
+        let src = r#"{"a": [1, -2, 3.5, "\u00e9"], "b\n": {"c": null, "d": true}}"#;
+
+        let value = parse_str(src).unwrap();
+
+        assert_eq!(
+            serde_json::Value::try_from(&value).unwrap(),
+            serde_json::from_str::<serde_json::Value>(src).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_value_spans() {
+        let src = "{\"a\": [1, true], \"b\": {\"c\": null}, \"d\": \"e\"}";
+
+        let value = parse_str(src).unwrap();
+
+        assert_eq!(value.leaf_spans(), vec!["1", "true", "null", "e"]);
+        assert_eq!(
+            value.spans(),
+            vec![src, "[1, true]", "1", "true", "{\"c\": null}", "null", "e"]
+        );
+    }
+
+    #[test]
+    fn test_value_without() {
+        let src = "{\"foo\": \"bar\", \"baz\": {\"a\": 1, \"b\": 2}}";
+
+        let value = parse_str(src).unwrap();
+
+        let indices = value.without(&["foo", "baz.b", "missing"]);
+        assert_eq!(
+            src.index_ranges(&indices),
+            "{\"foo\": \"\", \"baz\": {\"a\": 1, \"b\": }}"
+        );
+
+        assert!(value.try_without(&["foo", "missing"]).is_none());
+        assert_eq!(value.try_without(&["foo", "baz.b"]), Some(indices));
+    }
+
+    #[test]
+    fn test_value_path_at() {
+        let src = "{\"a\": {\"b\": [1, 22]}, \"c\": \"d\"}";
+
+        let value = parse_str(src).unwrap();
+
+        assert_eq!(value.path_at(17).as_deref(), Some("a.b.1"));
+        assert_eq!(value.path_at(15).as_deref(), Some("a.b"));
+        assert_eq!(value.path_at(3).as_deref(), Some(""));
+        assert_eq!(value.path_at(28).as_deref(), Some("c"));
+        assert_eq!(value.path_at(30).as_deref(), Some(""));
+        assert_eq!(value.path_at(src.len()), None);
+    }
+
+    #[test]
+    fn test_value_to_pretty_string() {
+        let src = "{\"a\": {\"b\": [1, \"x\\\"y\"]}, \"c\": [], \"d\": null}";
+
+        let value = parse_str(src).unwrap();
+
+        let (pretty, map) = value.to_pretty_string();
+
+        assert_eq!(
+            pretty,
+            "{\n  \"a\": {\n    \"b\": [\n      1,\n      \"x\\\"y\"\n    ]\n  },\n  \"c\": [],\n  \"d\": null\n}"
+        );
+
+        let paths = map
+            .iter()
+            .map(|(_, path)| path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["", "a", "a.b", "a.b.0", "a.b.1", "c", "d"]);
+
+        assert_eq!(map[0].0, 0..pretty.len());
+        assert_eq!(&pretty[map[3].0.clone()], "1");
+        assert_eq!(&pretty[map[4].0.clone()], "\"x\\\"y\"");
+        assert_eq!(&pretty[map[5].0.clone()], "[]");
+
+        // The output re-parses to the same structure.
+        assert_eq!(
+            parse_str(&pretty).unwrap().to_pretty_string(),
+            (pretty, map)
+        );
+    }
+
     #[test]
     fn test_key_value_without_value() {
         let src = "{\"foo\": \"bar\"\n}";
@@ -373,6 +1511,153 @@ mod tests {
         assert_eq!(src.index_ranges(&indices), "\"foo\": \"\"");
     }
 
+    #[test]
+    fn test_key_value_separator() {
+        let src = "{\"\":\"\", \"foo\"\n:\t[1]}";
+
+        let JsonValue::Object(value) = parse_str(src).unwrap() else {
+            panic!("expected object");
+        };
+
+        assert_eq!(value.elems[0].separator(), ":");
+        assert_eq!(value.elems[0].separator(), 3..4);
+        assert_eq!(value.elems[1].separator(), "\n:\t");
+        assert_eq!(value.elems[1].separator(), 13..16);
+    }
+
+    #[test]
+    fn test_key_value_span_with_trailing() {
+        let src = "{\"a\":1,\n  \"b\": {\"c\": null\t}\n}";
+
+        let JsonValue::Object(value) = parse_str(src).unwrap() else {
+            panic!("expected object");
+        };
+
+        assert_eq!(value.elems[0].span_with_trailing(), "\"a\":1,\n  ");
+        assert_eq!(value.elems[0].span_with_trailing(), 1..10);
+        assert_eq!(
+            value.elems[1].span_with_trailing(),
+            "\"b\": {\"c\": null\t}\n"
+        );
+
+        let JsonValue::Object(inner) = &value.elems[1].value else {
+            panic!("expected object");
+        };
+        assert_eq!(inner.elems[0].span(), "\"c\": null");
+        assert_eq!(inner.elems[0].span_with_trailing(), "\"c\": null\t");
+
+        // Spans are rebuilt against the original source.
+        let value = parse_str_jsonc("{\"a\": 1 /* c */}").unwrap();
+        let kv = &value.as_object().unwrap().elems[0];
+        assert_eq!(kv.span_with_trailing(), "\"a\": 1 /* c */");
+    }
+
+    #[test]
+    fn test_array_deletion_range() {
+        fn delete(src: &str, index: usize) -> Option<std::string::String> {
+            let value = parse_str(src).unwrap();
+            let indices = value.as_array().unwrap().deletion_range(index)?;
+
+            Some(
+                src.char_indices()
+                    .filter(|(idx, _)| !indices.contains(idx))
+                    .map(|(_, c)| c)
+                    .collect(),
+            )
+        }
+
+        let src = "[1 , \"a\",[2],\n {\"b\": 3}]";
+        assert_eq!(delete(src, 0).unwrap(), "[\"a\",[2],\n {\"b\": 3}]");
+        assert_eq!(delete(src, 1).unwrap(), "[1,[2],\n {\"b\": 3}]");
+        assert_eq!(delete(src, 3).unwrap(), "[1 , \"a\",[2]]");
+        assert!(delete(src, 4).is_none());
+
+        assert_eq!(delete("[ \"\" ]", 0).unwrap(), "[]");
+        assert_eq!(delete("[\"\", \"\", \"x\"]", 1).unwrap(), "[\"\", \"x\"]");
+        assert_eq!(delete("[\"\", \"\", \"x\"]", 2).unwrap(), "[\"\", \"\"]");
+        assert!(delete("[]", 0).is_none());
+
+        for src in [src, "[\"\", \"\", \"x\"]"] {
+            for index in 0..parse_str(src).unwrap().as_array().unwrap().len() {
+                assert!(parse_str(&delete(src, index).unwrap()).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_object_deletion_range() {
+        fn delete(src: &str, key: &str) -> Option<std::string::String> {
+            let value = parse_str(src).unwrap();
+            let indices = value.as_object().unwrap().deletion_range(key)?;
+
+            Some(
+                src.char_indices()
+                    .filter(|(idx, _)| !indices.contains(idx))
+                    .map(|(_, c)| c)
+                    .collect(),
+            )
+        }
+
+        assert_eq!(delete("{ \"a\": [1, 2] }", "a").unwrap(), "{}");
+
+        let src = "{\"a\": 1 ,\n \"b\": \"\", \"c\": {\"d\": null} }";
+        assert_eq!(
+            delete(src, "a").unwrap(),
+            "{\"b\": \"\", \"c\": {\"d\": null} }"
+        );
+        assert_eq!(
+            delete(src, "b").unwrap(),
+            "{\"a\": 1 ,\n \"c\": {\"d\": null} }"
+        );
+        assert_eq!(delete(src, "c").unwrap(), "{\"a\": 1 ,\n \"b\": \"\" }");
+        assert!(delete(src, "d").is_none());
+        assert!(delete("{}", "a").is_none());
+
+        for key in ["a", "b", "c"] {
+            assert!(parse_str(&delete(src, key).unwrap()).is_ok());
+        }
+
+        // Commas between pairs are optional.
+        assert_eq!(delete("{\"a\":1 \"b\":2}", "b").unwrap(), "{\"a\":1}");
+        assert_eq!(
+            delete("{\"a\":\"x\" \"b\":[1,2]}", "b").unwrap(),
+            "{\"a\":\"x\"}"
+        );
+        assert_eq!(
+            delete("{\"a\":[1,2] \"b\":2}", "b").unwrap(),
+            "{\"a\":[1,2]}"
+        );
+        assert_eq!(
+            delete("{\"a\":1 \"b\":2, \"c\":3}", "c").unwrap(),
+            "{\"a\":1 \"b\":2}"
+        );
+    }
+
+    #[test]
+    fn test_paths() {
+        let value = parse_str("{\"a\":[1,{\"b\":null,\"c\":[]}],\"d\":{},\"e\":\"\"}").unwrap();
+
+        let paths = value.paths();
+        assert_eq!(paths, vec!["a.0", "a.1.b", "a.1.c", "d", "e"]);
+        for path in &paths {
+            assert!(value.get(path).is_some());
+        }
+
+        assert_eq!(parse_str("1").unwrap().paths(), vec![""]);
+        assert_eq!(parse_str("[]").unwrap().paths(), vec![""]);
+
+        // Keys made of digits resolve, as a segment is only an index within an array.
+        let value = parse_str("{\"0\":[{\"1\":true}]}").unwrap();
+        assert_eq!(value.paths(), vec!["0.0.1"]);
+        assert_eq!(value.get("0.0.1").unwrap().span(), "true");
+
+        // Keys containing a `.` are not escaped, so their paths do not resolve.
+        let value = parse_str("{\"a.b\":1,\"a\":{\"b\":2}}").unwrap();
+        assert_eq!(value.paths(), vec!["a.b", "a.b"]);
+        assert_eq!(value.path_at(7).unwrap(), "a.b");
+        assert_eq!(value.get("a.b").unwrap().span(), "2");
+    }
+
     #[test]
     fn test_array_without_values() {
         let src = "[42, 14]";
@@ -386,6 +1671,28 @@ mod tests {
         assert_eq!(src.index_ranges(&indices), "[]");
     }
 
+    #[test]
+    fn test_array_structural() {
+        let src = "[1, 2, 3]";
+
+        let JsonValue::Array(value) = parse_str(src).unwrap() else {
+            panic!("expected array");
+        };
+
+        let indices = value.structural();
+
+        assert_eq!(indices, RangeSet::from([0..1, 2..4, 5..7, 8..9]));
+        assert_eq!(src.index_ranges(&indices), "[, , ]");
+
+        let src = "[ \"a\" ,{\"b\": 1}\n]";
+
+        let JsonValue::Array(value) = parse_str(src).unwrap() else {
+            panic!("expected array");
+        };
+
+        assert_eq!(src.index_ranges(&value.structural()), "[ \"\" ,\n]");
+    }
+
     #[test]
     fn test_object_without_pairs() {
         let src = "{\"foo\": \"bar\"\n}";
@@ -1,3 +1,5 @@
+use std::ops::ControlFlow;
+
 use super::{types, types::JsonValue};
 
 /// A visitor for JSON values.
@@ -82,3 +84,228 @@ pub trait JsonVisit {
     /// Visit a string value.
     fn visit_string(&mut self, _node: &types::String) {}
 }
+
+/// A visitor for JSON values which can stop the traversal early.
+///
+/// This is like [`JsonVisit`], except that each method returns a [`ControlFlow`]. Returning
+/// [`ControlFlow::Break`] stops the traversal, and the default methods propagate it to
+/// the caller.
+///
+/// # Example
+///
+/// ```
+/// use std::ops::ControlFlow;
+///
+/// use spansy::{
+///     json::{parse_str, JsonVisitControl, String},
+///     Spanned,
+/// };
+///
+/// #[derive(Default)]
+/// struct FindEmail {
+///     found: Option<String>,
+///     visited: usize,
+/// }
+///
+/// impl JsonVisitControl for FindEmail {
+///     fn visit_string(&mut self, node: &String) -> ControlFlow<()> {
+///         self.visited += 1;
+///         if node.span().as_str().contains('@') {
+///             self.found = Some(node.clone());
+///             return ControlFlow::Break(());
+///         }
+///         ControlFlow::Continue(())
+///     }
+/// }
+///
+/// let value = parse_str("[\"alice\", \"bob@example.com\", \"carol@example.com\"]").unwrap();
+///
+/// let mut visitor = FindEmail::default();
+/// assert!(visitor.visit_value(&value).is_break());
+///
+/// assert_eq!(visitor.found.unwrap().span(), "bob@example.com");
+/// assert_eq!(visitor.visited, 2);
+/// ```
+pub trait JsonVisitControl {
+    /// Visit a key value pair in a JSON object.
+    fn visit_key_value(&mut self, node: &types::KeyValue) -> ControlFlow<()> {
+        self.visit_key(&node.key)?;
+        self.visit_value(&node.value)
+    }
+
+    /// Visit a key in a JSON object.
+    fn visit_key(&mut self, _node: &types::JsonKey) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Visit a JSON value.
+    fn visit_value(&mut self, node: &JsonValue) -> ControlFlow<()> {
+        match node {
+            JsonValue::Null(value) => self.visit_null(value),
+            JsonValue::Bool(value) => self.visit_bool(value),
+            JsonValue::Number(value) => self.visit_number(value),
+            JsonValue::String(value) => self.visit_string(value),
+            JsonValue::Array(value) => self.visit_array(value),
+            JsonValue::Object(value) => self.visit_object(value),
+        }
+    }
+
+    /// Visit an array value.
+    fn visit_array(&mut self, node: &types::Array) -> ControlFlow<()> {
+        for elem in &node.elems {
+            self.visit_value(elem)?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Visit an object value.
+    fn visit_object(&mut self, node: &types::Object) -> ControlFlow<()> {
+        for kv in &node.elems {
+            self.visit_key_value(kv)?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Visit a null value.
+    fn visit_null(&mut self, _node: &types::Null) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Visit a boolean value.
+    fn visit_bool(&mut self, _node: &types::Bool) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Visit a number value.
+    fn visit_number(&mut self, _node: &types::Number) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Visit a string value.
+    fn visit_string(&mut self, _node: &types::String) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// A segment of the path to a JSON value, see [`JsonVisitPath`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathSegment<'a> {
+    /// An object key, as it appears in the source.
+    Key(&'a str),
+    /// An array index.
+    Index(usize),
+}
+
+impl PathSegment<'_> {
+    /// Joins the segments of a path into a dotted path, as accepted by
+    /// [`JsonValue::get`].
+    ///
+    /// Keys are not escaped, as [`JsonValue::get`] has no escape syntax and splits the
+    /// path at every `.`. The path of a value under a key containing a `.` therefore does
+    /// not resolve to that value. Keys made of digits are not ambiguous, as a segment is
+    /// only parsed as an index within an array.
+    pub fn join(path: &[PathSegment<'_>]) -> std::string::String {
+        let mut out = std::string::String::new();
+        for (idx, segment) in path.iter().enumerate() {
+            if idx > 0 {
+                out.push('.');
+            }
+            match segment {
+                PathSegment::Key(key) => out.push_str(key),
+                PathSegment::Index(index) => out.push_str(&index.to_string()),
+            }
+        }
+        out
+    }
+}
+
+/// A visitor for JSON values which is given the path to each visited node.
+///
+/// This is like [`JsonVisit`], except that each method also receives the path from the
+/// root value to the node. The path of a key is the path of its value. Paths are passed
+/// as segments rather than dotted strings, as building a string for every node is
+/// costly; use [`PathSegment::join`] to build one where needed. The default methods
+/// allocate a path buffer for each visited array and object.
+///
+/// # Example
+///
+/// ```
+/// use spansy::{
+///     json::{parse_str, JsonVisitPath, PathSegment, String},
+///     Spanned,
+/// };
+///
+/// #[derive(Default)]
+/// struct Redactor {
+///     redacted: Vec<std::string::String>,
+/// }
+///
+/// impl JsonVisitPath for Redactor {
+///     fn visit_string(&mut self, path: &[PathSegment<'_>], node: &String) {
+///         if path.starts_with(&[PathSegment::Key("user"), PathSegment::Key("ssn")]) {
+///             self.redacted.push(PathSegment::join(path));
+///         }
+///     }
+/// }
+///
+/// let value = parse_str("{\"user\": {\"name\": \"alice\", \"ssn\": \"123\"}}").unwrap();
+///
+/// let mut redactor = Redactor::default();
+/// redactor.visit_value(&[], &value);
+///
+/// assert_eq!(redactor.redacted, vec!["user.ssn"]);
+/// ```
+pub trait JsonVisitPath {
+    /// Visit a key value pair in a JSON object.
+    fn visit_key_value(&mut self, path: &[PathSegment<'_>], node: &types::KeyValue) {
+        self.visit_key(path, &node.key);
+        self.visit_value(path, &node.value);
+    }
+
+    /// Visit a key in a JSON object.
+    fn visit_key(&mut self, _path: &[PathSegment<'_>], _node: &types::JsonKey) {}
+
+    /// Visit a JSON value.
+    fn visit_value(&mut self, path: &[PathSegment<'_>], node: &JsonValue) {
+        match node {
+            JsonValue::Null(value) => self.visit_null(path, value),
+            JsonValue::Bool(value) => self.visit_bool(path, value),
+            JsonValue::Number(value) => self.visit_number(path, value),
+            JsonValue::String(value) => self.visit_string(path, value),
+            JsonValue::Array(value) => self.visit_array(path, value),
+            JsonValue::Object(value) => self.visit_object(path, value),
+        }
+    }
+
+    /// Visit an array value.
+    fn visit_array(&mut self, path: &[PathSegment<'_>], node: &types::Array) {
+        let mut elem_path = path.to_vec();
+        elem_path.push(PathSegment::Index(0));
+        for (idx, elem) in node.elems.iter().enumerate() {
+            *elem_path.last_mut().expect("path is not empty") = PathSegment::Index(idx);
+            self.visit_value(&elem_path, elem);
+        }
+    }
+
+    /// Visit an object value.
+    fn visit_object(&mut self, path: &[PathSegment<'_>], node: &types::Object) {
+        let mut kv_path = path.to_vec();
+        kv_path.push(PathSegment::Index(0));
+        for kv in &node.elems {
+            *kv_path.last_mut().expect("path is not empty") = PathSegment::Key(kv.key.0.as_str());
+            self.visit_key_value(&kv_path, kv);
+        }
+    }
+
+    /// Visit a null value.
+    fn visit_null(&mut self, _path: &[PathSegment<'_>], _node: &types::Null) {}
+
+    /// Visit a boolean value.
+    fn visit_bool(&mut self, _path: &[PathSegment<'_>], _node: &types::Bool) {}
+
+    /// Visit a number value.
+    fn visit_number(&mut self, _path: &[PathSegment<'_>], _node: &types::Number) {}
+
+    /// Visit a string value.
+    fn visit_string(&mut self, _path: &[PathSegment<'_>], _node: &types::String) {}
+}
@@ -3,15 +3,21 @@
 #![deny(missing_docs, unreachable_pub, unused_must_use)]
 #![deny(clippy::all)]
 
-use std::{fmt::Debug, marker::PhantomData, ops::Range};
+use std::{
+    borrow::Cow,
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Index, Range},
+};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 
 pub(crate) mod helpers;
 pub mod http;
 pub mod json;
 
-use utils::range::RangeSet;
+use utils::range::{RangeDifference, RangeSet, RangeUnion};
 
 /// A parsing error.
 #[derive(Debug, thiserror::Error)]
@@ -36,14 +42,55 @@ pub trait Spanned<T: ?Sized = [u8]> {
     fn span(&self) -> &Span<T>;
 }
 
+/// The source bytes a parsed value was parsed from.
+///
+/// The source is not part of the identity of the value: it is ignored by equality and
+/// hashing, its `Debug` output only includes its length, and it is not serialized, so
+/// deserialized values have an empty source.
+#[derive(Clone, Default)]
+pub(crate) struct Source(pub(crate) Bytes);
+
+impl PartialEq for Source {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Source {}
+
+impl Hash for Source {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl Debug for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Source")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
 /// A span of a source string.
+///
+/// A span holds its own bytes alongside the indices they were taken from. The bytes are
+/// resolved from the source when the span is created: they are the bytes at each range
+/// of the indices, concatenated in index order, so `data.len() == indices.len()` always
+/// holds. The indices are only metadata locating those bytes within the source. Shifting
+/// them, eg. with [`offset`](Span::offset), does not change the span data, and
+/// [`as_bytes`](Span::as_bytes), `Debug` and equality are all consistent with this.
 #[derive(PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span<T: ?Sized = [u8]> {
-    /// The original source bytes from when the span was parsed.
+    /// The bytes at the indices, concatenated in index order.
     pub(crate) data: Bytes,
-    /// The set of indices within the source data.
+    /// The set of indices within the source.
     pub(crate) indices: RangeSet<usize>,
+    /// The position of a zero-width span, see [`Span::point`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) point: Option<usize>,
     _pd: PhantomData<T>,
 }
 
@@ -52,6 +99,7 @@ impl Clone for Span<[u8]> {
         Self {
             data: self.data.clone(),
             indices: self.indices.clone(),
+            point: self.point,
             _pd: PhantomData,
         }
     }
@@ -62,6 +110,7 @@ impl Clone for Span<str> {
         Self {
             data: self.data.clone(),
             indices: self.indices.clone(),
+            point: self.point,
             _pd: PhantomData,
         }
     }
@@ -69,19 +118,27 @@ impl Clone for Span<str> {
 
 impl Debug for Span<[u8]> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Span")
+        let mut debug = f.debug_struct("Span");
+        debug
             .field("span", &self.as_bytes())
-            .field("indices", &self.indices)
-            .finish()
+            .field("indices", &self.indices);
+        if let Some(point) = self.point {
+            debug.field("point", &point);
+        }
+        debug.finish()
     }
 }
 
 impl Debug for Span<str> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Span")
+        let mut debug = f.debug_struct("Span");
+        debug
             .field("span", &self.as_str())
-            .field("indices", &self.indices)
-            .finish()
+            .field("indices", &self.indices);
+        if let Some(point) = self.point {
+            debug.field("point", &point);
+        }
+        debug.finish()
     }
 }
 
@@ -101,6 +158,110 @@ impl<T: ?Sized> Span<T> {
         &self.indices
     }
 
+    /// Returns the position of a zero-width span created with [`Span::point`].
+    ///
+    /// Returns `None` for any other span, including empty spans which are not a point.
+    pub fn position(&self) -> Option<usize> {
+        self.point
+    }
+
+    /// Returns a borrowed view of the span.
+    ///
+    /// Unlike cloning the span, this does not touch the reference count of the underlying
+    /// buffer, which makes it cheaper for read-only traversal in hot loops.
+    pub fn as_ref_view(&self) -> SpanRef<'_, T> {
+        SpanRef {
+            data: self.data.as_ref(),
+            indices: &self.indices,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Returns the contiguous ranges of the span indices.
+    ///
+    /// Unlike serializing the span, this does not include the span data, so it is a
+    /// compact record of which parts of a source the span covers. The span can be
+    /// reconstructed from the source with [`Span::from_ranges_vec`].
+    pub fn to_ranges_vec(&self) -> Vec<Range<usize>> {
+        self.indices.iter_ranges().collect()
+    }
+
+    /// Returns an iterator over the contiguous ranges of the span indices.
+    pub fn iter_ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.indices.iter_ranges()
+    }
+
+    /// Returns an iterator over the bytes of the span, in index order.
+    pub fn iter_bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.data.iter().copied()
+    }
+
+    /// Returns a copy of the bytes of the span, concatenated in index order.
+    ///
+    /// This is equivalent to `span.as_bytes().to_vec()`.
+    pub fn collect_bytes(&self) -> Vec<u8> {
+        self.data.to_vec()
+    }
+
+    /// Returns `true` if the spans contain the same bytes, regardless of their indices.
+    ///
+    /// Unlike the derived `PartialEq`, which also compares the indices, this can be used to
+    /// compare spans from different sources, eg. a span from one capture against a span
+    /// from a re-parse.
+    pub fn content_eq(&self, other: &Span<T>) -> bool {
+        self.data == other.data
+    }
+
+    /// Feeds the bytes of the span into the given hasher, regardless of its indices.
+    ///
+    /// This is consistent with [`content_eq`](Self::content_eq), see [`ContentSpan`].
+    pub fn content_hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state);
+    }
+
+    /// Returns `true` if the span contains the given index.
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices.contains(&index)
+    }
+
+    /// Returns `true` if the span contains every index of the given range.
+    ///
+    /// An empty range is always contained.
+    pub fn contains_range(&self, range: &Range<usize>) -> bool {
+        range.is_empty()
+            || self
+                .indices
+                .iter_ranges()
+                .any(|indices| indices.start <= range.start && range.end <= indices.end)
+    }
+
+    /// Returns the indices of the span as a single range if they are contiguous, otherwise
+    /// as a range set.
+    ///
+    /// An empty span has no indices, and is returned as an empty range set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use spansy::{SourceRange, Span};
+    ///
+    /// let src = Bytes::from_static(b"hello world");
+    ///
+    /// let span = Span::from_ranges(src.clone(), [0..5]);
+    /// assert_eq!(span.source_range(), SourceRange::Range(0..5));
+    ///
+    /// let span = Span::from_ranges(src, [0..2, 6..8]);
+    /// assert!(matches!(span.source_range(), SourceRange::Set(_)));
+    /// ```
+    pub fn source_range(&self) -> SourceRange {
+        let mut ranges = self.indices.iter_ranges();
+        match (ranges.next(), ranges.next()) {
+            (Some(range), None) => SourceRange::Range(range),
+            _ => SourceRange::Set(self.indices.clone()),
+        }
+    }
+
     /// Returns the length of the span in bytes.
     ///
     /// Just like `str::len()`, this is not necessarily the number of characters.
@@ -119,7 +280,258 @@ impl<T: ?Sized> Span<T> {
     ///
     /// Panics if the offset causes the indices to overflow `usize::MAX`.
     pub fn offset(&mut self, offset: usize) {
-        self.indices.shift_right(&offset);
+        if offset != 0 {
+            self.indices.shift_right(&offset);
+            if let Some(point) = &mut self.point {
+                *point = point.checked_add(offset).expect("span position overflows");
+            }
+        }
+    }
+
+    /// Returns a copy of the span with `new_origin` subtracted from every index.
+    ///
+    /// This is the inverse of [`offset`](Self::offset), and can be used to express a span
+    /// relative to a sub-slice of its source which starts at `new_origin`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index, or the position of a [point](Span::point), is less than
+    /// `new_origin`.
+    pub fn rebase(&self, new_origin: usize) -> Span<T> {
+        let ranges = self
+            .indices
+            .iter_ranges()
+            .map(|range| {
+                assert!(
+                    range.start >= new_origin,
+                    "span index {} is less than the new origin {new_origin}",
+                    range.start
+                );
+                range.start - new_origin..range.end - new_origin
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            data: self.data.clone(),
+            indices: RangeSet::new(&ranges),
+            point: self.point.map(|point| {
+                assert!(
+                    point >= new_origin,
+                    "span position {point} is less than the new origin {new_origin}"
+                );
+                point - new_origin
+            }),
+            _pd: PhantomData,
+        }
+    }
+
+    /// Creates a new span from the given indices within the source bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the indices are not within the source bytes.
+    fn from_indices(src: &Bytes, indices: RangeSet<usize>) -> Self {
+        for range in indices.iter_ranges() {
+            assert!(src.len() >= range.end, "span is not within source bytes");
+        }
+
+        // Avoid copying the data if the span is contiguous.
+        let data = if indices.len_ranges() == 1 {
+            src.slice(indices.iter_ranges().next().expect("span has one range"))
+        } else {
+            let mut data = BytesMut::with_capacity(indices.len());
+            for range in indices.iter_ranges() {
+                data.extend_from_slice(&src[range]);
+            }
+            data.freeze()
+        };
+
+        Self {
+            data,
+            indices,
+            point: None,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Returns a copy of the span with the indices shifted left by `origin` and the
+    /// data sliced from `src`.
+    ///
+    /// `src` must contain the source data starting at `origin`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is less than `origin`, or if the shifted indices are not
+    /// within `src`.
+    pub(crate) fn compact(&self, src: &Bytes, origin: usize) -> Self {
+        let ranges = self
+            .indices
+            .iter_ranges()
+            .map(|range| {
+                assert!(range.start >= origin, "span is not within source bytes");
+                range.start - origin..range.end - origin
+            })
+            .collect::<Vec<_>>();
+
+        Self::from_indices(src, RangeSet::new(&ranges))
+    }
+
+    /// Returns the span covering the given range relative to the start of this span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is not within the span.
+    fn slice(&self, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range {}..{} is not within span of length {}",
+            range.start,
+            range.end,
+            self.len()
+        );
+
+        let mut ranges = Vec::new();
+        let mut pos = 0;
+        for indices in self.indices.iter_ranges() {
+            let start = range.start.max(pos);
+            let end = range.end.min(pos + indices.len());
+
+            if start < end {
+                ranges.push(indices.start + start - pos..indices.start + end - pos);
+            }
+
+            pos += indices.len();
+        }
+
+        Self {
+            data: self.data.slice(range),
+            indices: RangeSet::new(&ranges),
+            point: None,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Merges the indices and data of two spans.
+    ///
+    /// If `intersect` is `true` only the indices present in both spans are kept,
+    /// otherwise all indices are kept. The data is taken from the source spans, and is
+    /// only copied if the result is not a contiguous part of either of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the spans disagree on the data at a shared index.
+    fn merge(&self, other: &Span<T>, intersect: bool) -> (Bytes, RangeSet<usize>) {
+        let overlap = self
+            .indices
+            .difference(&self.indices.difference(&other.indices));
+
+        let (mut lhs, mut rhs) = (self.cursor(), other.cursor());
+        for range in overlap.iter_ranges() {
+            let mut pos = range.start;
+            while pos < range.end {
+                let l = lhs.get(pos, range.end).expect("index is in both spans");
+                let r = rhs.get(pos, range.end).expect("index is in both spans");
+                let len = l.len().min(r.len());
+
+                assert_eq!(l[..len], r[..len], "spans are not from the same source");
+                pos += len;
+            }
+        }
+
+        let indices = if intersect {
+            overlap
+        } else {
+            self.indices.union(&other.indices)
+        };
+
+        for span in [self, other] {
+            if let Some(data) = span.data_for(&indices) {
+                return (data, indices);
+            }
+        }
+
+        // The data agrees where the spans overlap, so it is taken from whichever span
+        // contains an index, preferring this one.
+        let (mut lhs, mut rhs) = (self.cursor(), other.cursor());
+        let mut data = BytesMut::with_capacity(indices.len());
+        for range in indices.iter_ranges() {
+            let mut pos = range.start;
+            while pos < range.end {
+                let chunk = lhs
+                    .get(pos, range.end)
+                    .or_else(|| rhs.get(pos, range.end))
+                    .expect("index is in either span");
+
+                data.extend_from_slice(chunk);
+                pos += chunk.len();
+            }
+        }
+
+        (data.freeze(), indices)
+    }
+
+    /// Returns a cursor over the data of the span.
+    fn cursor(&self) -> Cursor<'_, impl Iterator<Item = Range<usize>> + '_> {
+        Cursor {
+            data: &self.data,
+            ranges: self.indices.iter_ranges().peekable(),
+            pos: 0,
+        }
+    }
+
+    /// Returns the data of the span at the given indices without copying, if they are
+    /// all of its indices or a single range within them.
+    fn data_for(&self, indices: &RangeSet<usize>) -> Option<Bytes> {
+        if indices == &self.indices {
+            return Some(self.data.clone());
+        }
+
+        let mut ranges = indices.iter_ranges();
+        let (Some(range), None) = (ranges.next(), ranges.next()) else {
+            return None;
+        };
+
+        let mut pos = 0;
+        for indices in self.indices.iter_ranges() {
+            if indices.start <= range.start && range.end <= indices.end {
+                let start = pos + range.start - indices.start;
+                return Some(self.data.slice(start..start + range.len()));
+            }
+            pos += indices.len();
+        }
+
+        None
+    }
+}
+
+/// A cursor over the data of a span, which is advanced by increasing indices.
+struct Cursor<'a, I: Iterator> {
+    data: &'a [u8],
+    ranges: std::iter::Peekable<I>,
+    /// The position in the data of the current range.
+    pos: usize,
+}
+
+impl<'a, I: Iterator<Item = Range<usize>>> Cursor<'a, I> {
+    /// Returns the data from `idx` up to `end`, or up to the end of the range of the
+    /// span containing `idx`, whichever comes first.
+    ///
+    /// Returns `None` if the span does not contain `idx`. Indices less than a previous
+    /// `idx` must not be passed.
+    fn get(&mut self, idx: usize, end: usize) -> Option<&'a [u8]> {
+        while let Some(range) = self.ranges.peek() {
+            if range.end > idx {
+                break;
+            }
+            self.pos += range.len();
+            self.ranges.next();
+        }
+
+        let range = self.ranges.peek().filter(|range| range.start <= idx)?;
+        let start = self.pos + idx - range.start;
+        let len = range.end.min(end) - idx;
+
+        Some(&self.data[start..start + len])
     }
 }
 
@@ -130,7 +542,7 @@ impl Span<str> {
     ///
     /// Panics if the given range is not within the source bytes, or
     /// if the span is not a valid UTF-8 string.
-    pub(crate) fn new_str(src: Bytes, range: Range<usize>) -> Self {
+    pub(crate) fn new_str(src: &Bytes, range: Range<usize>) -> Self {
         assert!(
             std::str::from_utf8(&src[range.clone()]).is_ok(),
             "span is not a valid UTF-8 string"
@@ -139,6 +551,7 @@ impl Span<str> {
         Self {
             data: src.slice(range.clone()),
             indices: range.into(),
+            point: None,
             _pd: PhantomData,
         }
     }
@@ -148,25 +561,201 @@ impl Span<str> {
     /// # Panics
     ///
     /// Panics if the given slice is not within the source bytes.
-    pub(crate) fn new_from_str(src: Bytes, span: &str) -> Self {
+    pub(crate) fn new_from_str(src: &Bytes, span: &str) -> Self {
         let range = helpers::get_span_range(src.as_ref(), span.as_bytes());
 
         Self {
             data: src.slice(range.clone()),
             indices: range.into(),
+            point: None,
             _pd: PhantomData,
         }
     }
 
+    /// Creates a new string span from the given ranges of the source bytes.
+    ///
+    /// The ranges may be disjoint, overlapping ranges are merged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the ranges are not within the source bytes, or if the
+    /// concatenated bytes of the ranges are not a valid UTF-8 string, as with
+    /// converting a byte span with [`TryFrom`].
+    pub fn from_ranges_str(src: Bytes, ranges: impl IntoIterator<Item = Range<usize>>) -> Self {
+        Span::from_ranges(src, ranges)
+            .try_into_str()
+            .expect("span is not a valid UTF-8 string")
+    }
+
     /// Converts this type to a string slice.
     pub fn as_str(&self) -> &str {
         self.as_ref()
     }
 
+    /// Returns the content of the span as a `Cow`.
+    ///
+    /// This is always borrowed, as for byte spans.
+    pub fn as_cow(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_str())
+    }
+
+    /// Returns the number of characters in the span.
+    ///
+    /// Unlike [`len`](Span::len), which returns the length in bytes, this counts Unicode
+    /// scalar values, ie. `str::chars().count()`. This is not necessarily the number of
+    /// graphemes perceived by a reader.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::{json, Spanned};
+    ///
+    /// let value = json::parse_str("{\"name\": \"Zoë\"}").unwrap();
+    /// let span = value.get("name").unwrap().span();
+    ///
+    /// assert_eq!(span.len(), 4);
+    /// assert_eq!(span.char_count(), 3);
+    /// ```
+    pub fn char_count(&self) -> usize {
+        self.as_str().chars().count()
+    }
+
     /// Returns the corresponding byte span.
     pub fn to_byte_span(&self) -> Span<[u8]> {
         self.into()
     }
+
+    /// Returns a subspan covering the given byte range, relative to the start of this span.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::{json, Spanned};
+    ///
+    /// let value = json::parse_str("{\"foo\": \"application/json\"}").unwrap();
+    /// let span = value.get("foo").unwrap().span();
+    ///
+    /// let subspan = span.subspan(0..11);
+    ///
+    /// assert_eq!(subspan, "application");
+    /// assert_eq!(subspan.indices(), 9..20);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is not within the span, or if the range does not lie
+    /// on UTF-8 character boundaries.
+    pub fn subspan(&self, range: Range<usize>) -> Span<str> {
+        assert!(
+            self.as_str().is_char_boundary(range.start)
+                && self.as_str().is_char_boundary(range.end),
+            "range is not on a UTF-8 character boundary"
+        );
+
+        self.slice(range)
+    }
+
+    /// Returns the union of this span and another span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the spans disagree on the data at a shared index, or if the union is not
+    /// a valid UTF-8 string.
+    pub fn union(&self, other: &Span<str>) -> Span<str> {
+        let (data, indices) = self.merge(other, false);
+
+        assert!(
+            std::str::from_utf8(&data).is_ok(),
+            "span is not a valid UTF-8 string"
+        );
+
+        Self {
+            data,
+            indices,
+            point: None,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Returns the intersection of this span and another span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the spans disagree on the data at a shared index, or if the intersection
+    /// is not a valid UTF-8 string.
+    pub fn intersection(&self, other: &Span<str>) -> Span<str> {
+        let (data, indices) = self.merge(other, true);
+
+        assert!(
+            std::str::from_utf8(&data).is_ok(),
+            "span is not a valid UTF-8 string"
+        );
+
+        Self {
+            data,
+            indices,
+            point: None,
+            _pd: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ?Sized> Span<T> {
+    /// Serializes only the indices of the span, without its data.
+    ///
+    /// The derived `Serialize` implementation includes the span data, which duplicates
+    /// the source for every span of a parsed message. This can be used with
+    /// `#[serde(serialize_with = "Span::serialize_indices")]` instead, and the span
+    /// reconstructed with [`Span::deserialize_with_source`].
+    pub fn serialize_indices<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.indices, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Span<[u8]> {
+    /// Deserializes the indices of a span serialized with [`Span::serialize_indices`],
+    /// resolving its data from the given source bytes.
+    ///
+    /// Returns an error if the indices are not within the source bytes.
+    pub fn deserialize_with_source<'de, D: serde::Deserializer<'de>>(
+        data: Bytes,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let indices: RangeSet<usize> = serde::Deserialize::deserialize(deserializer)?;
+
+        if let Some(range) = indices.iter_ranges().find(|range| range.end > data.len()) {
+            return Err(serde::de::Error::custom(format!(
+                "span range {}..{} exceeds source {}",
+                range.start,
+                range.end,
+                data.len()
+            )));
+        }
+
+        Ok(Self::from_indices(&data, indices))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Span<str> {
+    /// Deserializes the indices of a string span serialized with
+    /// [`Span::serialize_indices`], resolving its data from the given source bytes.
+    ///
+    /// Returns an error if the indices are not within the source bytes, or if the
+    /// bytes at the indices are not a valid UTF-8 string.
+    pub fn deserialize_with_source<'de, D: serde::Deserializer<'de>>(
+        data: Bytes,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Span::<[u8]>::deserialize_with_source(data, deserializer)?
+            .try_into_str()
+            .map_err(serde::de::Error::custom)
+    }
 }
 
 impl AsRef<str> for Span<str> {
@@ -190,20 +779,180 @@ impl Span<[u8]> {
     /// # Panics
     ///
     /// Panics if the given range is not within the source bytes.
-    pub(crate) fn new_bytes(src: Bytes, range: Range<usize>) -> Self {
+    pub(crate) fn new_bytes(src: &Bytes, range: Range<usize>) -> Self {
         assert!(src.len() >= range.end, "span is not within source bytes");
 
         Self {
             data: src.slice(range.clone()),
             indices: range.into(),
+            point: None,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Creates an empty byte span over the given source bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use spansy::Span;
+    ///
+    /// let span = Span::empty(Bytes::from_static(b"hello"));
+    ///
+    /// assert!(span.is_empty());
+    /// assert!(span.indices().is_empty());
+    /// ```
+    pub fn empty(data: Bytes) -> Self {
+        Self {
+            data: data.slice(0..0),
+            indices: RangeSet::default(),
+            point: None,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Creates a new zero-width span at the given position of the source bytes.
+    ///
+    /// The span is empty, but unlike [`Span::empty`] it retains its position, eg. to
+    /// mark where data would be inserted. The position is shifted along with the span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not within the source bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use spansy::Span;
+    ///
+    /// let mut span = Span::point(Bytes::from_static(b"hello"), 2);
+    /// assert!(span.is_empty());
+    /// assert_eq!(span.position(), Some(2));
+    ///
+    /// span.offset(3);
+    /// assert_eq!(span.position(), Some(5));
+    /// ```
+    pub fn point(data: Bytes, at: usize) -> Self {
+        assert!(at <= data.len(), "span is not within source bytes");
+
+        Self {
+            data: data.slice(at..at),
+            indices: RangeSet::default(),
+            point: Some(at),
             _pd: PhantomData,
         }
     }
 
+    /// Creates a new byte span from the given ranges of the source bytes.
+    ///
+    /// The ranges may be disjoint, overlapping ranges are merged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the ranges are not within the source bytes.
+    pub fn from_ranges(src: Bytes, ranges: impl IntoIterator<Item = Range<usize>>) -> Self {
+        let ranges = ranges.into_iter().collect::<Vec<_>>();
+        Self::from_indices(&src, RangeSet::new(&ranges))
+    }
+
+    /// Creates a new byte span from ranges of the source bytes returned by
+    /// [`Span::to_ranges_vec`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the ranges are not within the source bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use spansy::{http::parse_request, Span, Spanned};
+    ///
+    /// let src = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+    /// let req = parse_request(src).unwrap();
+    ///
+    /// let ranges = req.headers[0].span().to_ranges_vec();
+    /// let span = Span::from_ranges_vec(src.to_vec().into(), ranges);
+    ///
+    /// assert_eq!(&span, req.headers[0].span());
+    /// ```
+    pub fn from_ranges_vec(data: Bytes, ranges: Vec<Range<usize>>) -> Self {
+        Self::from_ranges(data, ranges)
+    }
+
     /// Converts this type to a byte slice.
+    ///
+    /// The span data is resolved from the source when the span is created, so for a
+    /// span with disjoint indices this is the concatenation of the bytes at each range,
+    /// not the contiguous region of the source between them.
     pub fn as_bytes(&self) -> &[u8] {
         self.as_ref()
     }
+
+    /// Returns the content of the span as a `Cow`.
+    ///
+    /// This is always borrowed, including for spans with disjoint indices, as their
+    /// content is concatenated when the span is created. It is provided for APIs which
+    /// accept either owned or borrowed content.
+    pub fn as_cow(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+
+    /// Converts the byte span into a string span.
+    ///
+    /// Returns an error if the span is not a valid UTF-8 string.
+    pub(crate) fn try_into_str(self) -> Result<Span<str>, std::str::Utf8Error> {
+        std::str::from_utf8(&self.data)?;
+
+        Ok(Span {
+            data: self.data,
+            indices: self.indices,
+            point: self.point,
+            _pd: PhantomData,
+        })
+    }
+
+    /// Returns a subspan covering the given byte range, relative to the start of this span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is not within the span.
+    pub fn subspan(&self, range: Range<usize>) -> Span<[u8]> {
+        self.slice(range)
+    }
+
+    /// Returns the union of this span and another span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the spans disagree on the data at a shared index.
+    pub fn union(&self, other: &Span<[u8]>) -> Span<[u8]> {
+        let (data, indices) = self.merge(other, false);
+
+        Self {
+            data,
+            indices,
+            point: None,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Returns the intersection of this span and another span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the spans disagree on the data at a shared index.
+    pub fn intersection(&self, other: &Span<[u8]>) -> Span<[u8]> {
+        let (data, indices) = self.merge(other, true);
+
+        Self {
+            data,
+            indices,
+            point: None,
+            _pd: PhantomData,
+        }
+    }
 }
 
 impl AsRef<[u8]> for Span<[u8]> {
@@ -212,11 +961,45 @@ impl AsRef<[u8]> for Span<[u8]> {
     }
 }
 
+/// Slices the content of the span, equivalent to `&span.as_bytes()[range]`.
+///
+/// The range is relative to the content of the span rather than the source, and for a
+/// span with disjoint indices indexes into the concatenated content.
+///
+/// # Panics
+///
+/// Panics if the range is out of bounds of the span content.
+impl Index<Range<usize>> for Span<[u8]> {
+    type Output = [u8];
+
+    fn index(&self, range: Range<usize>) -> &[u8] {
+        &self.as_bytes()[range]
+    }
+}
+
+/// Slices the content of the span, equivalent to `&span.as_str()[range]`.
+///
+/// The range is relative to the content of the span rather than the source, and for a
+/// span with disjoint indices indexes into the concatenated content.
+///
+/// # Panics
+///
+/// Panics if the range is out of bounds of the span content, or does not lie on `char`
+/// boundaries.
+impl Index<Range<usize>> for Span<str> {
+    type Output = str;
+
+    fn index(&self, range: Range<usize>) -> &str {
+        &self.as_str()[range]
+    }
+}
+
 impl From<Span<str>> for Span<[u8]> {
     fn from(span: Span<str>) -> Self {
         Self {
             data: span.data,
             indices: span.indices,
+            point: span.point,
             _pd: PhantomData,
         }
     }
@@ -227,11 +1010,23 @@ impl From<&Span<str>> for Span<[u8]> {
         Self {
             data: span.data.clone(),
             indices: span.indices.clone(),
+            point: span.point,
             _pd: PhantomData,
         }
     }
 }
 
+impl TryFrom<Span<[u8]>> for Span<str> {
+    type Error = ParseError;
+
+    /// Converts a byte span into a string span.
+    ///
+    /// Returns an error if the bytes at the span indices are not a valid UTF-8 string.
+    fn try_from(span: Span<[u8]>) -> Result<Self, Self::Error> {
+        Ok(span.try_into_str()?)
+    }
+}
+
 impl PartialEq<Span> for [u8] {
     fn eq(&self, other: &Span) -> bool {
         self == other.as_ref()
@@ -303,3 +1098,622 @@ impl<T: ?Sized> PartialEq<Span<T>> for &Range<usize> {
         other == *self
     }
 }
+
+/// The indices of a span, see [`Span::source_range`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceRange {
+    /// The indices are a single contiguous range.
+    Range(Range<usize>),
+    /// The indices are disjoint, or empty.
+    Set(RangeSet<usize>),
+}
+
+impl SourceRange {
+    /// Returns the contiguous range, if the indices are contiguous.
+    pub fn as_range(&self) -> Option<&Range<usize>> {
+        match self {
+            SourceRange::Range(range) => Some(range),
+            SourceRange::Set(_) => None,
+        }
+    }
+
+    /// Returns an iterator over the contiguous ranges of the indices.
+    pub fn iter_ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        let (range, set) = match self {
+            SourceRange::Range(range) => (Some(range.clone()), None),
+            SourceRange::Set(set) => (None, Some(set.iter_ranges())),
+        };
+
+        range.into_iter().chain(set.into_iter().flatten())
+    }
+}
+
+impl From<SourceRange> for RangeSet<usize> {
+    fn from(range: SourceRange) -> Self {
+        match range {
+            SourceRange::Range(range) => range.into(),
+            SourceRange::Set(set) => set,
+        }
+    }
+}
+
+/// A span which is compared and hashed by its bytes only, regardless of its indices.
+///
+/// This can be used to deduplicate spans from different sources, eg. in a `HashSet`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use spansy::{json, ContentSpan, Spanned};
+///
+/// let a = json::parse_str("{\"foo\": \"bar\"}").unwrap();
+/// let b = json::parse_str("[\"bar\"]").unwrap();
+///
+/// let mut set = HashSet::new();
+/// set.insert(ContentSpan(a.get("foo").unwrap().span().clone()));
+/// set.insert(ContentSpan(b.get("0").unwrap().span().clone()));
+///
+/// assert_eq!(set.len(), 1);
+/// ```
+pub struct ContentSpan<T: ?Sized = [u8]>(pub Span<T>);
+
+impl<T: ?Sized> Clone for ContentSpan<T>
+where
+    Span<T>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> Debug for ContentSpan<T>
+where
+    Span<T>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ContentSpan").field(&self.0).finish()
+    }
+}
+
+impl<T: ?Sized> PartialEq for ContentSpan<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.content_eq(&other.0)
+    }
+}
+
+impl<T: ?Sized> Eq for ContentSpan<T> {}
+
+impl<T: ?Sized> Hash for ContentSpan<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.content_hash(state);
+    }
+}
+
+/// A borrowed view of a [`Span`].
+///
+/// This borrows the span data and indices rather than owning a reference counted buffer,
+/// see [`Span::as_ref_view`].
+pub struct SpanRef<'a, T: ?Sized = [u8]> {
+    data: &'a [u8],
+    indices: &'a RangeSet<usize>,
+    _pd: PhantomData<T>,
+}
+
+impl<T: ?Sized> Clone for SpanRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for SpanRef<'_, T> {}
+
+impl Debug for SpanRef<'_, [u8]> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpanRef")
+            .field("span", &self.as_bytes())
+            .field("indices", &self.indices)
+            .finish()
+    }
+}
+
+impl Debug for SpanRef<'_, str> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpanRef")
+            .field("span", &self.as_str())
+            .field("indices", &self.indices)
+            .finish()
+    }
+}
+
+impl<'a, T: ?Sized> SpanRef<'a, T> {
+    /// Returns a reference to the span data.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the indices within the source data.
+    pub fn indices(&self) -> &'a RangeSet<usize> {
+        self.indices
+    }
+
+    /// Returns the length of the span in bytes.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns `true` if the span is empty.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+impl<'a> SpanRef<'a, [u8]> {
+    /// Returns the span as a byte slice.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> SpanRef<'a, str> {
+    /// Returns the span as a string slice.
+    pub fn as_str(&self) -> &'a str {
+        // # Safety
+        // The view borrows a `Span<str>`, which is guaranteed to be a valid UTF-8 string.
+        unsafe { std::str::from_utf8_unchecked(self.data) }
+    }
+
+    /// Returns the span as a byte slice.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &[u8] = b"hello world";
+
+    #[test]
+    fn test_span_union() {
+        let src = Bytes::from_static(SRC);
+        let a = Span::new_bytes(&src, 0..5);
+        let b = Span::new_bytes(&src, 3..8);
+        let c = Span::new_bytes(&src, 9..11);
+
+        let union = a.union(&b);
+        assert_eq!(union, 0..8);
+        assert_eq!(union.as_bytes(), b"hello wo");
+
+        let union = union.union(&c);
+        assert_eq!(union.indices(), &RangeSet::from([0..8, 9..11]));
+        assert_eq!(union.as_bytes(), b"hello wold");
+    }
+
+    #[test]
+    fn test_span_intersection() {
+        let src = Bytes::from_static(SRC);
+        let a = Span::new_str(&src, 0..5);
+        let b = Span::new_str(&src, 3..8);
+        let c = Span::new_str(&src, 9..11);
+
+        let intersection = a.intersection(&b);
+        assert_eq!(intersection, 3..5);
+        assert_eq!(intersection.as_str(), "lo");
+
+        assert!(a.intersection(&c).is_empty());
+    }
+
+    #[test]
+    fn test_span_merge_ranges() {
+        let src = Bytes::from_static(SRC);
+        let a = Span::from_ranges(src.clone(), [0..2, 4..7, 9..11]);
+        let b = Span::from_ranges(src.clone(), [1..5, 6..10]);
+
+        let union = a.union(&b);
+        assert_eq!(union, 0..11);
+        assert_eq!(union.as_bytes(), SRC);
+
+        let intersection = a.intersection(&b);
+        assert_eq!(
+            intersection.indices(),
+            &RangeSet::from([1..2, 4..5, 6..7, 9..10])
+        );
+        assert_eq!(intersection.as_bytes(), b"eowl");
+        assert_eq!(b.intersection(&a), intersection);
+
+        // The data is not copied if the result is contiguous in either span.
+        let c = Span::new_bytes(&src, 2..9);
+        let union = c.union(&Span::new_bytes(&src, 3..5));
+        assert_eq!(union.data.as_ptr(), c.data.as_ptr());
+        let intersection = a.intersection(&c);
+        assert_eq!(intersection.as_bytes(), b"o w");
+        assert_eq!(intersection.data.as_ptr(), a.data[2..].as_ptr());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_span_intersection_different_source() {
+        let a = Span::new_bytes(&Bytes::from_static(b"hello"), 0..5);
+        let b = Span::new_bytes(&Bytes::from_static(b"help"), 2..4);
+
+        a.intersection(&b);
+    }
+
+    #[test]
+    fn test_span_subspan() {
+        let src = Bytes::from_static(SRC);
+        let span = Span::new_bytes(&src, 0..5).union(&Span::new_bytes(&src, 6..11));
+
+        let subspan = span.subspan(3..8);
+        assert_eq!(subspan.indices(), &RangeSet::from([3..5, 6..9]));
+        assert_eq!(subspan.as_bytes(), b"lowor");
+
+        assert!(span.subspan(4..4).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_span_subspan_out_of_range() {
+        let span = Span::new_bytes(&Bytes::from_static(SRC), 6..11);
+
+        span.subspan(0..6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_span_subspan_char_boundary() {
+        let span = Span::new_str(&Bytes::from("\u{e9}t\u{e9}"), 0..5);
+
+        span.subspan(0..1);
+    }
+
+    #[test]
+    fn test_span_from_ranges() {
+        let src = Bytes::from_static(SRC);
+
+        let span = Span::from_ranges(src.clone(), [6..11, 0..2, 1..4]);
+        assert_eq!(span.indices(), &RangeSet::from([0..4, 6..11]));
+        assert_eq!(span.as_bytes(), b"hellworld");
+
+        let span = Span::from_ranges_str(src, [0..2, 2..5]);
+        assert_eq!(span, "hello");
+        assert_eq!(span, 0..5);
+    }
+
+    #[test]
+    fn test_span_data_invariant() {
+        let src = Bytes::from_static(SRC);
+        let mut span = Span::from_ranges(src.clone(), [0..2, 6..8]);
+        assert_eq!(span.data.len(), span.indices.len());
+
+        span.offset(3);
+        assert_eq!(span.indices(), &RangeSet::from([3..5, 9..11]));
+        assert_eq!(span.as_bytes(), b"hewo");
+        assert_eq!(
+            format!("{span:?}"),
+            format!(
+                "Span {{ span: {:?}, indices: {:?} }}",
+                b"hewo", span.indices
+            )
+        );
+
+        let subspan = span.subspan(1..3);
+        assert_eq!(subspan.indices(), &RangeSet::from([4..5, 9..10]));
+        assert_eq!(subspan.as_bytes(), b"ew");
+
+        // Same bytes at different indices are not equal.
+        let other = Span::from_ranges(src, [0..2, 6..8]);
+        assert!(span.content_eq(&other));
+        assert_ne!(span, other);
+    }
+
+    #[test]
+    fn test_span_empty() {
+        let src = Bytes::from_static(SRC);
+
+        let span = Span::empty(src.clone());
+        assert!(span.is_empty());
+        assert!(span.indices().is_empty());
+        assert_eq!(span.as_bytes(), b"");
+        assert_eq!(span, Span::new_bytes(&src, 3..3));
+    }
+
+    #[test]
+    fn test_span_point() {
+        let src = Bytes::from_static(SRC);
+
+        let span = Span::point(src.clone(), 5);
+        assert!(span.is_empty());
+        assert_eq!(span.position(), Some(5));
+        assert_ne!(span, Span::empty(src.clone()));
+        assert_ne!(span, Span::point(src.clone(), 6));
+        assert_eq!(Span::empty(src.clone()).position(), None);
+
+        let mut shifted = span.clone();
+        shifted.offset(10);
+        assert_eq!(shifted.position(), Some(15));
+        assert_eq!(shifted.rebase(10), span);
+
+        let span = Span::<str>::try_from(span).unwrap();
+        assert_eq!(span.position(), Some(5));
+        assert_eq!(Span::<[u8]>::from(span).position(), Some(5));
+
+        assert_eq!(
+            Span::point(src.clone(), SRC.len()).position(),
+            Some(SRC.len())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_span_point_out_of_bounds() {
+        Span::point(Bytes::from_static(SRC), SRC.len() + 1);
+    }
+
+    #[test]
+    fn test_span_try_from_bytes() {
+        let src = Bytes::from("h\u{e9}llo");
+
+        let span = Span::<str>::try_from(Span::from_ranges(src.clone(), [0..3, 5..6])).unwrap();
+        assert_eq!(span, "h\u{e9}o");
+        assert_eq!(span.indices(), &RangeSet::from([0..3, 5..6]));
+
+        // The ranges split a multi-byte character.
+        assert!(Span::<str>::try_from(Span::from_ranges(src.clone(), [0..2, 5..6])).is_err());
+        assert!(Span::<str>::try_from(Span::new_bytes(&src, 2..3)).is_err());
+    }
+
+    #[test]
+    fn test_span_ranges_vec_round_trip() {
+        let src = Bytes::from_static(SRC);
+        let span = Span::from_ranges(src.clone(), [0..2, 6..8]);
+
+        let ranges = span.to_ranges_vec();
+        assert_eq!(ranges, vec![0..2, 6..8]);
+
+        let round_trip = Span::from_ranges_vec(src.clone(), ranges);
+        assert_eq!(round_trip.indices(), span.indices());
+        assert_eq!(round_trip, span);
+
+        assert!(Span::empty(src).to_ranges_vec().is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    fn test_span_deserialize_with_source() {
+        let src = Bytes::from("h\u{e9}llo");
+        let span = Span::new_str(&src, 0..3);
+
+        let mut indices = Vec::new();
+        span.serialize_indices(&mut serde_json::Serializer::new(&mut indices))
+            .unwrap();
+        assert!(indices.len() < serde_json::to_vec(&span).unwrap().len());
+
+        let deserialize = |src: Bytes, json: &[u8]| {
+            Span::<str>::deserialize_with_source(
+                src,
+                &mut serde_json::Deserializer::from_slice(json),
+            )
+        };
+
+        assert_eq!(deserialize(src.clone(), &indices).unwrap(), span);
+
+        // The indices split a multi-byte character.
+        let mut json = Vec::new();
+        Span::new_bytes(&src, 0..2)
+            .serialize_indices(&mut serde_json::Serializer::new(&mut json))
+            .unwrap();
+        assert!(deserialize(src.clone(), &json).is_err());
+
+        // The indices are not within the source.
+        assert!(deserialize(src.slice(0..2), &indices).is_err());
+    }
+
+    #[test]
+    fn test_span_collect_bytes() {
+        let span = Span::from_ranges(Bytes::from_static(SRC), [0..2, 6..8]);
+
+        assert_eq!(span.collect_bytes(), b"hewo");
+        assert_eq!(span.collect_bytes(), span.as_bytes());
+        assert_eq!(span.collect_bytes(), span.iter_bytes().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_span_index() {
+        let span = Span::new_bytes(&Bytes::from_static(SRC), 6..11);
+        assert_eq!(&span[1..4], b"orl");
+        assert_eq!(&span[0..0], b"");
+
+        let span = Span::from_ranges(Bytes::from_static(SRC), [0..2, 6..8]);
+        assert_eq!(&span[1..3], b"ew");
+
+        let span = Span::new_str(&Bytes::from_static(SRC), 0..5);
+        assert_eq!(&span[2..5], "llo");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_span_index_out_of_bounds() {
+        let span = Span::new_bytes(&Bytes::from_static(SRC), 6..11);
+        let _ = &span[3..6];
+    }
+
+    #[test]
+    fn test_span_as_cow() {
+        let span = Span::new_bytes(&Bytes::from_static(SRC), 0..5);
+        assert!(matches!(span.as_cow(), Cow::Borrowed(b"hello")));
+
+        let span = Span::from_ranges(Bytes::from_static(SRC), [0..2, 6..8]);
+        assert!(matches!(span.as_cow(), Cow::Borrowed(b"hewo")));
+
+        let span = Span::from_ranges_str(Bytes::from_static(SRC), [0..2, 6..8]);
+        assert!(matches!(span.as_cow(), Cow::Borrowed("hewo")));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_span_from_ranges_out_of_bounds() {
+        Span::from_ranges(Bytes::from_static(SRC), [0..2, 6..12]);
+    }
+
+    #[test]
+    fn test_span_from_ranges_str_concatenated() {
+        // Neither range is valid UTF-8 on its own, but their concatenation is.
+        let src = Bytes::from_static(b"\xc3x\xa9");
+
+        let span = Span::from_ranges_str(src.clone(), [0..1, 2..3]);
+        assert_eq!(span.as_str(), "\u{e9}");
+        assert_eq!(
+            Span::<str>::try_from(Span::from_ranges(src, [0..1, 2..3])).unwrap(),
+            span
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_span_from_ranges_str_invalid_utf8() {
+        Span::from_ranges_str(Bytes::from("\u{e9}t\u{e9}"), [0..1, 2..5]);
+    }
+
+    #[test]
+    fn test_span_rebase() {
+        let span = Span::from_ranges(Bytes::from_static(SRC), [0..2, 6..8]);
+
+        let mut shifted = span.clone();
+        shifted.offset(10);
+        assert_eq!(shifted.indices(), &RangeSet::from([10..12, 16..18]));
+
+        assert_eq!(shifted.rebase(10), span);
+        assert_eq!(shifted.rebase(4).indices(), &RangeSet::from([6..8, 12..14]));
+        assert_eq!(shifted.rebase(4).as_bytes(), b"hewo");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_span_rebase_below_origin() {
+        Span::new_bytes(&Bytes::from_static(SRC), 2..5).rebase(3);
+    }
+
+    #[test]
+    fn test_span_ref_view() {
+        let src = Bytes::from_static(SRC);
+
+        let span = Span::from_ranges(src.clone(), [0..2, 6..8]);
+        let view = span.as_ref_view();
+        assert_eq!(view.as_bytes(), b"hewo");
+        assert_eq!(view.indices(), span.indices());
+        assert_eq!(view.len(), 4);
+
+        let span = Span::new_str(&src, 6..11);
+        let view = span.as_ref_view();
+        assert_eq!(view.as_str(), "world");
+        assert_eq!(view.as_bytes(), b"world");
+        assert_eq!(view.indices(), &RangeSet::from(6..11));
+        assert!(!view.is_empty());
+    }
+
+    #[test]
+    fn test_span_content_eq() {
+        let a = Span::new_str(&Bytes::from_static(b"hello world"), 6..11);
+        let b = Span::new_str(&Bytes::from_static(b"world"), 0..5);
+        let c = Span::new_str(&Bytes::from_static(b"hello words"), 6..11);
+
+        assert!(a.content_eq(&b));
+        assert_ne!(a, b);
+        assert!(!a.content_eq(&c));
+    }
+
+    #[test]
+    fn test_content_span_hash() {
+        let a = Span::new_bytes(&Bytes::from_static(b"hello world"), 6..11);
+        let b = Span::new_bytes(&Bytes::from_static(b"world"), 0..5);
+        let c = Span::new_bytes(&Bytes::from_static(b"words"), 0..5);
+
+        let set = [a.clone(), b.clone()]
+            .into_iter()
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(set.len(), 2);
+
+        let set = [a, b, c]
+            .into_iter()
+            .map(ContentSpan)
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_span_source_range() {
+        let src = Bytes::from_static(SRC);
+
+        let span = Span::new_bytes(&src, 2..7);
+        let range = span.source_range();
+        assert_eq!(range, SourceRange::Range(2..7));
+        assert_eq!(range.as_range(), Some(&(2..7)));
+        assert_eq!(range.iter_ranges().collect::<Vec<_>>(), vec![2..7]);
+        assert_eq!(RangeSet::from(range), RangeSet::from(2..7));
+
+        // Adjacent ranges are contiguous.
+        let span = Span::from_ranges(src.clone(), [0..2, 2..4]);
+        assert_eq!(span.source_range(), SourceRange::Range(0..4));
+
+        let span = Span::from_ranges(src.clone(), [0..2, 6..8]);
+        let range = span.source_range();
+        assert_eq!(range, SourceRange::Set(RangeSet::from([0..2, 6..8])));
+        assert!(range.as_range().is_none());
+        assert_eq!(range.iter_ranges().collect::<Vec<_>>(), vec![0..2, 6..8]);
+        assert_eq!(RangeSet::from(range), span.indices);
+
+        let range = Span::empty(src).source_range();
+        assert_eq!(range, SourceRange::Set(RangeSet::default()));
+        assert_eq!(range.iter_ranges().count(), 0);
+    }
+
+    #[test]
+    fn test_span_contains() {
+        let span = Span::from_ranges(Bytes::from_static(SRC), [0..2, 6..8]);
+
+        assert!(span.contains(0));
+        assert!(span.contains(7));
+        assert!(!span.contains(2));
+        assert!(!span.contains(8));
+
+        assert!(span.contains_range(&(0..2)));
+        assert!(span.contains_range(&(6..7)));
+        assert!(!span.contains_range(&(0..7)));
+        assert!(!span.contains_range(&(7..9)));
+    }
+
+    #[test]
+    fn test_span_iter() {
+        let src = Bytes::from_static(SRC);
+        let span = Span::new_bytes(&src, 0..2).union(&Span::new_bytes(&src, 6..8));
+
+        assert_eq!(span.iter_ranges().collect::<Vec<_>>(), vec![0..2, 6..8]);
+        assert_eq!(span.iter_bytes().collect::<Vec<_>>(), b"hewo");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_span_union_different_source() {
+        let a = Span::new_bytes(&Bytes::from_static(b"foo"), 0..3);
+        let b = Span::new_bytes(&Bytes::from_static(b"bar"), 0..3);
+
+        a.union(&b);
+    }
+
+    #[test]
+    fn test_span_merge_disjoint_different_source() {
+        // Spans from different sources are not detected if they do not share an index.
+        let a = Span::new_bytes(&Bytes::from_static(b"foo"), 0..3);
+        let b = Span::new_bytes(&Bytes::from_static(b"barbaz"), 3..6);
+
+        let union = a.union(&b);
+        assert_eq!(union, 0..6);
+        assert_eq!(union.as_bytes(), b"foobaz");
+
+        assert!(a.intersection(&b).is_empty());
+    }
+}